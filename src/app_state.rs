@@ -8,6 +8,8 @@ pub enum AppState {
     LoadingMenu,
     MainMenu,
     Credits,
+    Controls,
+    Upgrades,
     InGame,
     Levels,
     ToNextLevel,
@@ -5,7 +5,7 @@ use bevy::{
 use bevy_asset_loader::prelude::{AssetCollection, LoadingState, LoadingStateAppExt};
 use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
 use bevy_turborand::{DelegatedRng, GlobalRng, TurboRand};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{app_state::AppState, in_game::Levels, menus::credits::Credits};
 
@@ -25,7 +25,7 @@ impl Plugin for MainGameAssetPlugin {
     }
 }
 
-#[derive(Copy, Clone, Default, Reflect, Deserialize, Resource)]
+#[derive(Copy, Clone, Default, Reflect, Deserialize, Serialize, Resource)]
 pub enum Locale {
     #[default]
     Forest,
@@ -0,0 +1,73 @@
+use std::{env, fs, path::PathBuf};
+
+use game::song_analysis::{
+    analyze, decode, generate_level, ASSETS_DIRECTORY, CUSTOM_LEVELS_FILE, CUSTOM_MUSIC_DIRECTORY,
+};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let input = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(CUSTOM_MUSIC_DIRECTORY));
+    let output = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(CUSTOM_LEVELS_FILE));
+
+    let mut songs = match fs::read_dir(&input) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|v| v.to_str())
+                    .map(|v| matches!(v.to_lowercase().as_str(), "flac" | "mp3"))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            eprintln!("Couldn't read {input:?}: {e}");
+            std::process::exit(1);
+        }
+    };
+    songs.sort();
+
+    let mut levels = vec![];
+    for path in songs {
+        let Ok(song) = path.strip_prefix(ASSETS_DIRECTORY) else {
+            eprintln!("Skipping {path:?} - songs need to live inside {ASSETS_DIRECTORY:?}");
+            continue;
+        };
+        let (samples, sample_rate) = match decode(&path) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                eprintln!("{e}");
+                continue;
+            }
+        };
+        let analysis = analyze(&samples, sample_rate);
+        let name = path
+            .file_stem()
+            .and_then(|v| v.to_str())
+            .unwrap_or("Custom Level");
+        let song = song.to_string_lossy().replace('\\', "/");
+        println!(
+            "{name}: {:.1}s at {:.0} BPM",
+            analysis.duration, analysis.bpm
+        );
+        levels.push(generate_level(name, &song, &analysis));
+    }
+
+    let contents = match serde_yaml::to_string(&levels) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Couldn't serialize levels: {e}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = fs::write(&output, contents) {
+        eprintln!("Couldn't write {output:?}: {e}");
+        std::process::exit(1);
+    }
+    println!("Wrote {} levels to {output:?}", levels.len());
+}
@@ -1,10 +1,8 @@
-use bevy::{
-    prelude::{KeyCode, MouseButton},
-    reflect::Reflect,
-};
+use bevy::{input::mouse::MouseMotion, prelude::*, utils::HashMap};
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum PlayerAction {
     Move,
     Teleport,
@@ -12,39 +10,301 @@ pub enum PlayerAction {
     ConsumeCheckpointHealth,
     SendDevilToCheckpoint,
     Secondary,
+    ToggleAimAssist,
+    CycleTarget,
+    CycleCheckpoint,
+    SlowTime,
+    Cloak,
+    Ritual,
 }
 
-pub fn input_manager() -> InputManagerBundle<PlayerAction> {
-    InputManagerBundle {
-        action_state: ActionState::default(),
-        input_map: InputMap::new([
-            (UserInput::from(KeyCode::Space), PlayerAction::Teleport),
-            (
-                VirtualDPad {
-                    up: KeyCode::W.into(),
-                    down: KeyCode::S.into(),
-                    left: KeyCode::A.into(),
-                    right: KeyCode::D.into(),
-                }
-                .into(),
-                PlayerAction::Move,
+impl PlayerAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayerAction::Move => "Move",
+            PlayerAction::Teleport => "Teleport",
+            PlayerAction::Target => "Aim",
+            PlayerAction::ConsumeCheckpointHealth => "Heal From Checkpoint",
+            PlayerAction::SendDevilToCheckpoint => "Banish",
+            PlayerAction::Secondary => "Use Checkpoint",
+            PlayerAction::ToggleAimAssist => "Toggle Aim Assist",
+            PlayerAction::CycleTarget => "Cycle Target",
+            PlayerAction::CycleCheckpoint => "Cycle Checkpoint",
+            PlayerAction::SlowTime => "Slow Time",
+            PlayerAction::Cloak => "Cloak Person",
+            PlayerAction::Ritual => "Perform Ritual",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum BindingDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Binding {
+    pub action: PlayerAction,
+    #[serde(default)]
+    pub direction: Option<BindingDirection>,
+    pub input: InputKind,
+}
+
+impl Binding {
+    fn new(action: PlayerAction, input: impl Into<InputKind>) -> Self {
+        Self {
+            action,
+            direction: None,
+            input: input.into(),
+        }
+    }
+
+    fn dpad(action: PlayerAction, [up, down, left, right]: [KeyCode; 4]) -> [Self; 4] {
+        [
+            (BindingDirection::Up, up),
+            (BindingDirection::Down, down),
+            (BindingDirection::Left, left),
+            (BindingDirection::Right, right),
+        ]
+        .map(|(direction, input)| Self {
+            action,
+            direction: Some(direction),
+            input: input.into(),
+        })
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        matches!(
+            self.input,
+            InputKind::GamepadButton(_) | InputKind::SingleAxis(_) | InputKind::DualAxis(_)
+        )
+    }
+
+    pub fn is_axis(&self) -> bool {
+        is_axis(&self.input)
+    }
+
+    pub fn accepts(&self, input: &InputKind) -> bool {
+        self.is_axis() == is_axis(input)
+    }
+
+    pub fn label(&self) -> String {
+        match self.direction {
+            Some(direction) => format!("{} {direction:?}", self.action.label()),
+            None => self.action.label().to_string(),
+        }
+    }
+}
+
+fn is_axis(input: &InputKind) -> bool {
+    matches!(input, InputKind::SingleAxis(_) | InputKind::DualAxis(_))
+}
+
+pub fn input_label(input: &InputKind) -> String {
+    match input {
+        InputKind::Keyboard(key) => format!("{key:?}"),
+        InputKind::Mouse(button) => format!("Mouse {button:?}"),
+        InputKind::GamepadButton(button) => format!("Gamepad {button:?}"),
+        other => format!("{other:?}"),
+    }
+}
+
+pub const CONTROLS_FILE: &str = "controls";
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct Controls {
+    pub bindings: Vec<Binding>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        let mut bindings = vec![Binding::new(PlayerAction::Teleport, KeyCode::Space)];
+        bindings.extend(Binding::dpad(
+            PlayerAction::Move,
+            [KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D],
+        ));
+        bindings.extend(Binding::dpad(
+            PlayerAction::Target,
+            [KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right],
+        ));
+        bindings.extend([
+            Binding::new(PlayerAction::ConsumeCheckpointHealth, KeyCode::Key1),
+            Binding::new(PlayerAction::SendDevilToCheckpoint, KeyCode::Key2),
+            Binding::new(PlayerAction::Secondary, KeyCode::ShiftLeft),
+            Binding::new(PlayerAction::ToggleAimAssist, KeyCode::Tab),
+            Binding::new(PlayerAction::CycleTarget, KeyCode::Q),
+            Binding::new(PlayerAction::CycleCheckpoint, KeyCode::E),
+            Binding::new(PlayerAction::SlowTime, KeyCode::ControlLeft),
+            Binding::new(PlayerAction::Cloak, KeyCode::F),
+            Binding::new(PlayerAction::Ritual, KeyCode::R),
+            Binding::new(PlayerAction::Teleport, MouseButton::Left),
+            Binding::new(PlayerAction::Secondary, MouseButton::Right),
+            Binding::new(PlayerAction::Move, DualAxis::left_stick()),
+            Binding::new(PlayerAction::Target, DualAxis::right_stick()),
+            Binding::new(PlayerAction::Teleport, GamepadButtonType::RightTrigger2),
+            Binding::new(PlayerAction::Secondary, GamepadButtonType::LeftTrigger2),
+            Binding::new(
+                PlayerAction::ConsumeCheckpointHealth,
+                GamepadButtonType::South,
             ),
-            (
-                VirtualDPad {
-                    up: KeyCode::Up.into(),
-                    down: KeyCode::Down.into(),
-                    left: KeyCode::Left.into(),
-                    right: KeyCode::Right.into(),
-                }
-                .into(),
-                PlayerAction::Target,
+            Binding::new(PlayerAction::SendDevilToCheckpoint, GamepadButtonType::East),
+            Binding::new(PlayerAction::ToggleAimAssist, GamepadButtonType::North),
+            Binding::new(PlayerAction::CycleTarget, GamepadButtonType::RightTrigger),
+            Binding::new(
+                PlayerAction::CycleCheckpoint,
+                GamepadButtonType::LeftTrigger,
             ),
-            (KeyCode::Key1.into(), PlayerAction::ConsumeCheckpointHealth),
-            (KeyCode::Key2.into(), PlayerAction::SendDevilToCheckpoint),
-            (KeyCode::ShiftLeft.into(), PlayerAction::Secondary),
-            (MouseButton::Left.into(), PlayerAction::Teleport),
-            (MouseButton::Right.into(), PlayerAction::Secondary),
-        ])
-        .build(),
+            Binding::new(PlayerAction::SlowTime, GamepadButtonType::LeftThumb),
+            Binding::new(PlayerAction::Cloak, GamepadButtonType::West),
+            Binding::new(PlayerAction::Ritual, GamepadButtonType::RightThumb),
+        ]);
+        Self { bindings }
+    }
+}
+
+impl Controls {
+    pub fn with_missing_defaults(mut self) -> Self {
+        for default in Controls::default().bindings {
+            let bound = self.bindings.iter().any(|binding| {
+                binding.action == default.action
+                    && binding.direction == default.direction
+                    && binding.is_gamepad() == default.is_gamepad()
+            });
+            if !bound {
+                self.bindings.push(default);
+            }
+        }
+        self
+    }
+
+    pub fn conflict(&self, index: usize, input: &InputKind) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .enumerate()
+            .find(|(i, binding)| *i != index && binding.input == *input)
+            .map(|(_, binding)| binding)
+    }
+
+    pub fn input_map_for_player(&self, index: usize, players: usize) -> InputMap<PlayerAction> {
+        if players <= 1 {
+            return self.filtered_input_map(|_| true);
+        }
+        match InputDevice::for_player(index) {
+            InputDevice::KeyboardMouse => self.filtered_input_map(|v| !v.is_gamepad()),
+            InputDevice::Gamepad => {
+                let mut input_map = self.filtered_input_map(Binding::is_gamepad);
+                input_map.set_gamepad(Gamepad::new(index - 1));
+                input_map
+            }
+        }
+    }
+
+    fn filtered_input_map(&self, filter: impl Fn(&Binding) -> bool) -> InputMap<PlayerAction> {
+        let mut input_map = InputMap::default();
+        let mut dpads: HashMap<PlayerAction, [Vec<InputKind>; 4]> = HashMap::default();
+
+        for binding in self.bindings.iter().filter(|v| filter(v)) {
+            match binding.direction {
+                None => {
+                    input_map.insert(binding.input, binding.action);
+                }
+                Some(direction) => {
+                    dpads.entry(binding.action).or_default()[direction as usize]
+                        .push(binding.input);
+                }
+            }
+        }
+
+        for (action, [up, down, left, right]) in dpads {
+            let count = up.len().max(down.len()).max(left.len()).max(right.len());
+            for i in 0..count {
+                let (Some(up), Some(down), Some(left), Some(right)) =
+                    (up.get(i), down.get(i), left.get(i), right.get(i))
+                else {
+                    continue;
+                };
+                input_map.insert(
+                    VirtualDPad {
+                        up: *up,
+                        down: *down,
+                        left: *left,
+                        right: *right,
+                    },
+                    action,
+                );
+            }
+        }
+
+        input_map
+    }
+}
+
+#[derive(Resource, Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+impl InputDevice {
+    pub fn for_player(index: usize) -> Self {
+        if index == 0 {
+            InputDevice::KeyboardMouse
+        } else {
+            InputDevice::Gamepad
+        }
+    }
+}
+
+const GAMEPAD_AXIS_THRESHOLD: f32 = 0.3;
+
+pub fn detect_input_device(
+    mut device: ResMut<InputDevice>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+) {
+    let gamepad_used = gamepad_buttons.get_just_pressed().next().is_some()
+        || gamepads.iter().any(|gamepad| {
+            [
+                GamepadAxisType::LeftStickX,
+                GamepadAxisType::LeftStickY,
+                GamepadAxisType::RightStickX,
+                GamepadAxisType::RightStickY,
+            ]
+            .into_iter()
+            .filter_map(|axis_type| gamepad_axes.get(GamepadAxis::new(gamepad, axis_type)))
+            .any(|value| value.abs() > GAMEPAD_AXIS_THRESHOLD)
+        });
+    let keyboard_mouse_used = keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || mouse_motion.iter().count() > 0;
+
+    let next = if gamepad_used {
+        InputDevice::Gamepad
+    } else if keyboard_mouse_used {
+        InputDevice::KeyboardMouse
+    } else {
+        return;
+    };
+    if *device != next {
+        *device = next;
+    }
+}
+
+pub fn input_manager(
+    controls: &Controls,
+    index: usize,
+    players: usize,
+) -> InputManagerBundle<PlayerAction> {
+    InputManagerBundle {
+        action_state: ActionState::default(),
+        input_map: controls.input_map_for_player(index, players),
     }
 }
@@ -0,0 +1,161 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    actions::PlayerAction,
+    lantern_bearer::Lantern,
+    player::{move_target, PlayerTarget, PlayerTargetReference},
+    schedule::InGameUpdate,
+    shadow::{lit_by_lantern, Shadow, ShadowCollisionGrid},
+    teleport::CanTeleport,
+};
+
+pub fn aim_assist_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(
+        InGameUpdate,
+        (toggle_aim_assist, snap_target).chain().after(move_target),
+    )
+    .add_systems(PostUpdate, draw_aim_assist);
+}
+
+const AIM_CONE: f32 = 0.5;
+const AIM_RESET_ALIGNMENT: f32 = 0.9;
+
+#[derive(Component, Debug)]
+pub struct AimAssist {
+    pub enabled: bool,
+    pub aim: Vec2,
+    pub selected: usize,
+    pub candidates: Vec<Vec3>,
+}
+
+impl Default for AimAssist {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            aim: Vec2::X,
+            selected: 0,
+            candidates: vec![],
+        }
+    }
+}
+
+impl AimAssist {
+    pub fn landing_point(&self) -> Option<Vec3> {
+        self.candidates.get(self.selected).copied()
+    }
+}
+
+fn toggle_aim_assist(mut players: Query<(&mut AimAssist, &ActionState<PlayerAction>)>) {
+    for (mut aim_assist, actions) in &mut players {
+        if actions.just_pressed(PlayerAction::ToggleAimAssist) {
+            aim_assist.enabled = !aim_assist.enabled;
+            aim_assist.selected = 0;
+            aim_assist.candidates.clear();
+        }
+    }
+}
+
+fn snap_target(
+    mut players: Query<(
+        &GlobalTransform,
+        &CanTeleport,
+        &PlayerTargetReference,
+        &mut AimAssist,
+        &ActionState<PlayerAction>,
+    )>,
+    mut targets: Query<&mut Transform, With<PlayerTarget>>,
+    shadows: Query<&GlobalTransform, With<Shadow>>,
+    lanterns: Query<(&GlobalTransform, &Lantern)>,
+    grid: Res<ShadowCollisionGrid>,
+) {
+    let lanterns = lanterns
+        .iter()
+        .map(|(transform, lantern)| (transform.translation(), lantern.radius))
+        .collect::<Box<[_]>>();
+
+    for (transform, can_teleport, target_ref, mut aim_assist, actions) in &mut players {
+        if !aim_assist.enabled {
+            continue;
+        }
+        let position = transform.translation();
+
+        let aim = match targets.get(target_ref.0) {
+            Ok(target) => {
+                let target = target.translation.xy();
+                let snapped = aim_assist
+                    .landing_point()
+                    .map(|v| v.xy().distance(target) < 1.)
+                    .unwrap_or_default();
+                if snapped {
+                    Vec2::ZERO
+                } else {
+                    (target - position.xy()).normalize_or_zero()
+                }
+            }
+            Err(_) => Vec2::ZERO,
+        };
+        if aim != Vec2::ZERO && aim.dot(aim_assist.aim) < AIM_RESET_ALIGNMENT {
+            aim_assist.selected = 0;
+            aim_assist.aim = aim;
+        }
+        let aim = aim_assist.aim;
+
+        let mut candidates = grid
+            .nearby(position.xy(), can_teleport.max_distance)
+            .filter_map(|v| shadows.get(v).ok())
+            .map(|v| v.translation())
+            .filter(|v| !lit_by_lantern(*v, &lanterns))
+            .filter_map(|v| {
+                let offset = (v - position).xy();
+                let distance = offset.length();
+                if distance > can_teleport.max_distance || distance < can_teleport.min_distance {
+                    return None;
+                }
+                let alignment = offset.normalize_or_zero().dot(aim);
+                (alignment > AIM_CONE).then_some((v, alignment, distance))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.2.total_cmp(&b.2)));
+        aim_assist.candidates = candidates.into_iter().map(|v| v.0).collect();
+
+        if aim_assist.candidates.is_empty() {
+            aim_assist.selected = 0;
+            continue;
+        }
+
+        if actions.just_pressed(PlayerAction::CycleTarget) {
+            aim_assist.selected += 1;
+        }
+        aim_assist.selected %= aim_assist.candidates.len();
+
+        let Some(landing) = aim_assist.landing_point() else {
+            continue;
+        };
+        if let Ok(mut target) = targets.get_mut(target_ref.0) {
+            target.translation = Vec3::new(landing.x, landing.y, 0.);
+        }
+    }
+}
+
+fn draw_aim_assist(players: Query<&AimAssist>, mut painter: ShapePainter, time: Res<Time>) {
+    let pulse = (time.elapsed_seconds() * 6.).sin() * 0.5 + 0.5;
+    for aim_assist in &players {
+        if !aim_assist.enabled {
+            continue;
+        }
+        painter.hollow = true;
+        for (i, candidate) in aim_assist.candidates.iter().enumerate() {
+            painter.set_translation(*candidate);
+            if i == aim_assist.selected {
+                painter.color = crate::ui::colors::PRIMARY_COLOR;
+                painter.circle(12. + 4. * pulse);
+            } else {
+                painter.color = crate::ui::colors::PRIMARY_COLOR.with_a(0.3);
+                painter.circle(6.);
+            }
+        }
+    }
+}
@@ -7,13 +7,15 @@ use crate::assets::WithMesh;
 
 use super::{
     danger::{
-        Chase, Chasing, Danger, DangerSpawner, DangerType, Resting, Restlessness, Shoot, Shooting,
-        Shot, SpawnTime,
+        Chase, Chasing, Danger, DangerSpawner, DangerType, Patrol, Patrolling, Resting,
+        Restlessness, Shoot, Shooting, Shot, SpawnTime,
     },
+    game_clock::GameClock,
     movement::{CanMove, Moving},
     player::Player,
     schedule::InGameUpdate,
     souls::LethalTouch,
+    teleport::PathInteraction,
     InGame,
 };
 
@@ -33,13 +35,14 @@ pub fn angelic_archer_plugin(app: &mut ReloadableAppContents) {
 fn spawn_angelic_archer(
     dangers: Query<Entity, (With<AngelicArcher>, Without<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
         commands.entity(danger).insert((
             Name::new("Angelic Archer"),
             Danger(10.),
+            PathInteraction::Damage(10.),
             CanMove { move_speed: 200. },
             SpawnTime(now),
             Restlessness {
@@ -75,6 +78,7 @@ fn spawn_angelic_archer(
                         target_distance: 150.,
                     },
                 )
+                .when(Patrol, Patrolling)
                 .otherwise(Resting),
             WithMesh::AngelicArchers,
         ));
@@ -101,6 +105,7 @@ fn shooting(
             AngelicArrow,
             Name::new("Angelic Arrow"),
             Danger(10.),
+            PathInteraction::Damage(20.),
             CanMove { move_speed: 200. },
             Moving(direction),
             WithMesh::AngelicArrow,
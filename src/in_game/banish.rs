@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use bevy_tweening::TweenCompleted;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use super::{
+    boss::{Boss, BossHit},
+    checkpoints::Checkpoints,
+    co_op::GameMode,
+    danger::Danger,
+    game_clock::GameClock,
+    game_state::TemporaryIgnore,
+    player::{Player, PlayerTarget, PlayerTargetReference},
+    schedule::InGameUpdate,
+    teleport::{StartTeleport, TELEPORT_COMPLETED_EVENT},
+    InGame,
+};
+
+pub fn banish_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(InGameUpdate, (find_banish_target, recover_from_stun))
+        .add_systems(
+            PostUpdate,
+            (
+                banish,
+                stun_on_arrival,
+                draw_banish_target,
+                draw_banish_effect,
+            ),
+        );
+}
+
+const BANISH_TARGET_RANGE: f32 = 2.;
+const BANISH_STUN_DURATION: f32 = 1.5;
+const BANISH_EFFECT_DURATION: f32 = 0.6;
+const VERSUS_BANISH_OFFSET: f32 = 120.;
+
+#[derive(Component)]
+pub struct BanishImmune;
+
+#[derive(Component, Clone, Copy, Debug)]
+#[component(storage = "SparseSet")]
+pub struct BanishCandidate(pub Entity);
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Banish {
+    pub player: Entity,
+    pub danger: Entity,
+    pub destination: Vec3,
+    pub souls: f32,
+}
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct Banished;
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub struct Stunned(pub f32);
+
+#[derive(Component)]
+struct BanishEffect(f32);
+
+fn find_banish_target(
+    players: Query<(Entity, &PlayerTargetReference, &Checkpoints), With<Player>>,
+    targets: Query<&GlobalTransform, With<PlayerTarget>>,
+    dangers: Query<
+        (Entity, &GlobalTransform, &Danger),
+        (Without<BanishImmune>, Without<TemporaryIgnore>),
+    >,
+    mut commands: Commands,
+) {
+    for (player, target_ref, checkpoints) in &players {
+        let candidate = targets
+            .get(target_ref.0)
+            .ok()
+            .filter(|_| !checkpoints.checkpoints.is_empty())
+            .and_then(|target| {
+                let target = target.translation();
+                dangers
+                    .iter()
+                    .map(|(danger, position, radius)| {
+                        (danger, position.translation().distance(target), radius.0)
+                    })
+                    .filter(|(_, distance, radius)| *distance < radius * BANISH_TARGET_RANGE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(danger, _, _)| danger)
+            });
+
+        if let Some(candidate) = candidate {
+            commands.entity(player).insert(BanishCandidate(candidate));
+        } else {
+            commands.entity(player).remove::<BanishCandidate>();
+        }
+    }
+}
+
+fn banish(
+    bosses: Query<(), With<Boss>>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut events: EventReader<Banish>,
+    mut boss_hits: EventWriter<BossHit>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+    mode: Res<GameMode>,
+) {
+    let now = time.elapsed_seconds();
+    for event in events.iter() {
+        if bosses.contains(event.danger) {
+            boss_hits.send(BossHit {
+                boss: event.danger,
+                amount: event.souls,
+            });
+            continue;
+        }
+        let destination = if mode.is_versus() {
+            opponent_destination(&players, event.player).unwrap_or(event.destination)
+        } else {
+            event.destination
+        };
+        let Some(mut danger) = commands.get_entity(event.danger) else {
+            continue;
+        };
+        danger.insert((StartTeleport(destination), Banished));
+        commands.spawn((
+            SpatialBundle {
+                transform: Transform::from_translation(destination),
+                ..Default::default()
+            },
+            BanishEffect(now),
+            InGame,
+        ));
+    }
+}
+
+fn opponent_destination(
+    players: &Query<(Entity, &GlobalTransform), With<Player>>,
+    sender: Entity,
+) -> Option<Vec3> {
+    let origin = players.get(sender).ok()?.1.translation();
+    let opponent = players
+        .iter()
+        .filter(|(player, _)| *player != sender)
+        .map(|(_, transform)| transform.translation())
+        .min_by(|a, b| a.distance(origin).total_cmp(&b.distance(origin)))?;
+    Some(opponent + (origin - opponent).normalize_or_zero() * VERSUS_BANISH_OFFSET)
+}
+
+fn stun_on_arrival(
+    banished: Query<Entity, With<Banished>>,
+    mut event: EventReader<TweenCompleted>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for event in event.iter() {
+        if event.user_data != TELEPORT_COMPLETED_EVENT {
+            continue;
+        }
+        if let Ok(danger) = banished.get(event.entity) {
+            commands
+                .entity(danger)
+                .remove::<Banished>()
+                .insert(Stunned(now + BANISH_STUN_DURATION));
+        }
+    }
+}
+
+fn recover_from_stun(
+    stunned: Query<(Entity, &Stunned)>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for (entity, stunned) in &stunned {
+        if now > stunned.0 {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn draw_banish_target(
+    players: Query<(&BanishCandidate, &Checkpoints)>,
+    dangers: Query<(&GlobalTransform, &Danger)>,
+    mut painter: ShapePainter,
+) {
+    for (candidate, checkpoints) in &players {
+        let Ok((transform, radius)) = dangers.get(candidate.0) else {
+            continue;
+        };
+        painter.hollow = true;
+        painter.color = crate::ui::colors::PRIMARY_COLOR;
+        painter.set_translation(transform.translation());
+        painter.circle(radius.0 * 1.5);
+
+        if let Some(checkpoint) = checkpoints.selected() {
+            painter.color = crate::ui::colors::PRIMARY_COLOR.with_a(0.5);
+            painter.set_translation(checkpoint.position);
+            painter.circle(radius.0);
+        }
+    }
+}
+
+fn draw_banish_effect(
+    effects: Query<(Entity, &GlobalTransform, &BanishEffect)>,
+    mut painter: ShapePainter,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for (entity, transform, effect) in &effects {
+        let progress = (now - effect.0) / BANISH_EFFECT_DURATION;
+        if progress > 1. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        painter.hollow = true;
+        painter.color = crate::ui::colors::PRIMARY_COLOR.with_a(1. - progress);
+        painter.set_translation(transform.translation());
+        painter.circle(20. + 40. * progress);
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::InspectorOptions;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::AppState;
+
+use super::{
+    game_clock::GameClock,
+    movement::CanMove,
+    ritual::{Ritual, RitualProceeding},
+    schedule::InGameUpdate,
+    CurrentLevel,
+};
+
+pub fn beat_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_beat_clock)
+        .add_systems(
+            PreUpdate,
+            advance_beat_clock.run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(InGameUpdate, step_on_beat)
+        .add_systems(PostUpdate, draw_ritual_beat);
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Serialize, Debug, Default)]
+#[serde(default)]
+pub struct TempoSettings {
+    pub bpm: f32,
+    pub offset: f32,
+    pub beats: Vec<f32>,
+}
+
+impl TempoSettings {
+    pub fn beat_time(&self, index: usize) -> Option<f32> {
+        if !self.beats.is_empty() {
+            self.beats.get(index).copied()
+        } else if self.bpm > 0. {
+            Some(self.offset + index as f32 * 60. / self.bpm)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Beat {
+    pub index: usize,
+    pub time: f32,
+}
+
+#[derive(Component)]
+pub struct LevelSong;
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct BeatClock {
+    pub tempo: Option<TempoSettings>,
+    pub anchor: Option<SongAnchor>,
+    pub position: f32,
+    pub next_beat: usize,
+    pub last_beat: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SongAnchor {
+    pub position: f32,
+    pub at: f32,
+    pub speed: f32,
+    pub playing: bool,
+}
+
+impl SongAnchor {
+    fn position(&self, now: f32) -> f32 {
+        if self.playing {
+            self.position + (now - self.at) * self.speed
+        } else {
+            self.position
+        }
+    }
+}
+
+impl BeatClock {
+    pub fn phase(&self) -> Option<f32> {
+        let tempo = self.tempo.as_ref()?;
+        let last = self.last_beat?;
+        let next = tempo.beat_time(self.next_beat)?;
+        let interval = next - last;
+        (interval > 0.).then(|| ((self.position - last) / interval).clamp(0., 1.))
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct StepsOnBeat {
+    pub base_speed: f32,
+    pub stride: f32,
+}
+
+const BEAT_STRIDE: f32 = 3.;
+const STRIDE_DECAY: f32 = 6.;
+
+fn reset_beat_clock(mut commands: Commands, level: Res<CurrentLevel>) {
+    commands.insert_resource(BeatClock {
+        tempo: level.tempo.clone(),
+        ..Default::default()
+    });
+}
+
+// Audio plays in real time, so the clock follows the song's sink against real
+// time, re-anchoring whenever the sink is paused, resumed or changes speed.
+fn advance_beat_clock(
+    mut clock: ResMut<BeatClock>,
+    songs: Query<Ref<AudioSink>, With<LevelSong>>,
+    mut beats: EventWriter<Beat>,
+    time: Res<Time>,
+) {
+    let Some(tempo) = clock.tempo.clone() else {
+        return;
+    };
+    let Ok(song) = songs.get_single() else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    let position = match clock.anchor {
+        Some(anchor) if !song.is_added() => anchor.position(now),
+        _ => 0.,
+    };
+    let playing = !song.is_paused();
+    let speed = song.speed();
+    let moved = clock
+        .anchor
+        .map(|v| v.playing != playing || v.speed != speed)
+        .unwrap_or(true);
+    if moved || song.is_added() {
+        clock.anchor = Some(SongAnchor {
+            position,
+            at: now,
+            speed,
+            playing,
+        });
+    }
+    clock.position = position;
+
+    while let Some(at) = tempo.beat_time(clock.next_beat) {
+        if at > clock.position {
+            break;
+        }
+        beats.send(Beat {
+            index: clock.next_beat,
+            time: at,
+        });
+        clock.last_beat = Some(at);
+        clock.next_beat += 1;
+    }
+}
+
+fn step_on_beat(
+    clock: Res<BeatClock>,
+    mut beats: EventReader<Beat>,
+    mut steppers: Query<(&mut CanMove, &mut StepsOnBeat)>,
+    time: Res<GameClock>,
+) {
+    if clock.tempo.as_ref().and_then(|v| v.beat_time(0)).is_none() {
+        return;
+    }
+    let on_beat = beats.iter().count() > 0;
+    let delta = time.delta_seconds();
+    for (mut can_move, mut stepper) in &mut steppers {
+        stepper.stride = if on_beat {
+            BEAT_STRIDE
+        } else {
+            (stepper.stride - STRIDE_DECAY * delta).max(0.)
+        };
+        can_move.move_speed = stepper.base_speed * stepper.stride;
+    }
+}
+
+fn draw_ritual_beat(
+    clock: Res<BeatClock>,
+    rituals: Query<&Ritual, With<RitualProceeding>>,
+    mut painter: ShapePainter,
+) {
+    let Some(phase) = clock.phase() else {
+        return;
+    };
+    painter.hollow = true;
+    painter.color = crate::ui::colors::BAD_COLOR.with_a(1. - phase);
+    for ritual in &rituals {
+        painter.set_translation(ritual.position.extend(-1.));
+        painter.circle(ritual.radius * (1. + 0.5 * phase));
+    }
+}
@@ -0,0 +1,447 @@
+use std::{f32::consts::TAU, ops::Mul};
+
+use bevy::{ecs::query::Has, math::Vec3Swizzles, prelude::*};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_turborand::{DelegatedRng, GlobalRng, TurboRand};
+use bevy_ui_dsl::*;
+use big_brain::{
+    prelude::{ActionBuilder, ActionState, FirstToScore, ScorerBuilder},
+    scorers::Score,
+    thinker::{ActionSpan, Actor, Thinker},
+};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    assets::WithMesh,
+    ui::classes::{boss_bar, boss_bar_container, boss_bar_root, standard_text},
+};
+
+use super::{
+    danger::{danger_marker, Danger, DangerSpawner, DangerType, Resting},
+    escort::DangerTarget,
+    game_clock::GameClock,
+    movement::{CanMove, Moving},
+    player::Player,
+    ritual::{Ritual, TimeSoFar},
+    schedule::{InGameActions, InGameScorers, InGameUpdate},
+    souls::{DamageType, Death, LethalTouch, MaxSouls, Souls},
+    teleport::PathInteraction,
+    CurrentLevel, InGame,
+};
+
+pub fn boss_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_encounter)
+        .add_systems(InGameScorers, boss_phase_scorer_system)
+        .add_systems(InGameActions, boss_phase_action_system)
+        .add_systems(
+            InGameUpdate,
+            (
+                spawn_boss,
+                (hit_boss, defeat_boss, update_boss_phase).chain(),
+                track_boss_catch,
+                setup_boss_ui,
+            ),
+        )
+        .add_systems(PostUpdate, draw_boss_ui);
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(default)]
+pub struct BossDefinition {
+    pub name: String,
+    pub mesh: Option<String>,
+    pub health: f32,
+    pub radius: f32,
+    pub appear_before_end: f32,
+    pub goal: BossGoal,
+    pub phases: Vec<BossPhase>,
+}
+
+impl Default for BossDefinition {
+    fn default() -> Self {
+        Self {
+            name: "The Crossroads Keeper".to_string(),
+            mesh: None,
+            health: 100.,
+            radius: 25.,
+            appear_before_end: 20.,
+            goal: BossGoal::Defeat,
+            phases: vec![
+                BossPhase::default(),
+                BossPhase {
+                    below_health: 0.5,
+                    behavior: BossBehavior::Summon,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum BossGoal {
+    #[default]
+    Defeat,
+    Evade,
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(default)]
+pub struct BossPhase {
+    pub below_health: f32,
+    pub behavior: BossBehavior,
+    pub move_speed: f32,
+    pub orbit_radius: f32,
+    pub summon: DangerType,
+    pub summon_interval: f32,
+}
+
+impl Default for BossPhase {
+    fn default() -> Self {
+        Self {
+            below_health: 1.,
+            behavior: BossBehavior::Orbit,
+            move_speed: 80.,
+            orbit_radius: 150.,
+            summon: DangerType::StealthySeraphim,
+            summon_interval: 4.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, Default)]
+pub enum BossBehavior {
+    Chase,
+    #[default]
+    Orbit,
+    Summon,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct BossEncounter {
+    pub spawned: bool,
+    pub defeated: bool,
+    pub caught: bool,
+}
+
+impl BossEncounter {
+    pub fn satisfied(&self, boss: Option<&BossDefinition>) -> bool {
+        match boss.map(|v| v.goal) {
+            None => true,
+            Some(BossGoal::Defeat) => self.defeated,
+            Some(BossGoal::Evade) => !self.caught,
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BossHit {
+    pub boss: Entity,
+    pub amount: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct Boss {
+    pub phase: usize,
+    pub phases: Vec<BossPhase>,
+    pub home: Vec2,
+    pub last_summon: f32,
+}
+
+#[derive(Component)]
+struct BossSummoned;
+
+fn reset_encounter(mut commands: Commands) {
+    commands.insert_resource(BossEncounter::default());
+}
+
+fn spawn_boss(
+    rituals: Query<(Entity, &Ritual, &TimeSoFar), Without<BossSummoned>>,
+    level: Res<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+    mut encounter: ResMut<BossEncounter>,
+    mut commands: Commands,
+) {
+    let Some(definition) = &level.boss else {
+        return;
+    };
+    for (ritual_entity, ritual, time) in &rituals {
+        if time.0 < ritual.end_time - definition.appear_before_end {
+            continue;
+        }
+        commands.entity(ritual_entity).insert(BossSummoned);
+        encounter.spawned = true;
+
+        let mut phases = definition.phases.clone();
+        if phases.is_empty() {
+            phases.push(BossPhase::default());
+        }
+        phases.sort_by(|a, b| b.below_health.total_cmp(&a.below_health));
+
+        let thinker = phases.iter().enumerate().fold(
+            Thinker::build()
+                .label("Boss Thinker")
+                .picker(FirstToScore { threshold: 0.8 }),
+            |thinker, (phase, _)| {
+                thinker.when(BossPhaseScorer { phase }, BossPhaseAction { phase })
+            },
+        );
+
+        info!("Spawning boss {}", definition.name);
+
+        commands.spawn((
+            Name::new(definition.name.clone()),
+            SpatialBundle {
+                transform: Transform::from_translation(ritual.position.extend(0.))
+                    .with_scale(Vec3::ONE * 2.),
+                ..Default::default()
+            },
+            (
+                Danger(definition.radius),
+                DangerType::GuardianAngel,
+                DangerSpawner(ritual_entity),
+                LethalTouch,
+                PathInteraction::Block,
+            ),
+            CanMove {
+                move_speed: phases[0].move_speed,
+            },
+            Souls(definition.health),
+            MaxSouls(definition.health),
+            Boss {
+                phase: 0,
+                phases,
+                home: ritual.position,
+                last_summon: 0.,
+            },
+            thinker.otherwise(Resting),
+            if let Some(mesh) = &definition.mesh {
+                WithMesh::Handle(asset_server.load(mesh.as_str()))
+            } else {
+                WithMesh::GuardianAngel
+            },
+            InGame,
+        ));
+    }
+}
+
+fn hit_boss(
+    mut bosses: Query<(Entity, &mut Souls), With<Boss>>,
+    mut hits: EventReader<BossHit>,
+    mut death: EventWriter<Death>,
+) {
+    for hit in hits.iter() {
+        let Ok((boss, mut souls)) = bosses.get_mut(hit.boss) else {
+            continue;
+        };
+        souls.0 -= hit.amount;
+        if souls.0 <= 0. {
+            death.send(Death {
+                entity: boss,
+                cause: DamageType::Boss,
+            });
+        }
+    }
+}
+
+fn defeat_boss(
+    bosses: Query<Entity, With<Boss>>,
+    mut events: EventReader<Death>,
+    mut encounter: ResMut<BossEncounter>,
+    mut commands: Commands,
+) {
+    for death in events.iter() {
+        let Ok(boss) = bosses.get(death.entity) else {
+            continue;
+        };
+        info!("Boss defeated");
+        encounter.defeated = true;
+        commands.entity(boss).despawn_recursive();
+    }
+}
+
+fn update_boss_phase(mut bosses: Query<(&mut Boss, &mut CanMove, &Souls, &MaxSouls)>) {
+    for (mut boss, mut can_move, souls, max_souls) in &mut bosses {
+        let health = souls.0 / max_souls.0;
+        let phase = boss
+            .phases
+            .iter()
+            .rposition(|v| health <= v.below_health)
+            .unwrap_or_default();
+        if phase != boss.phase {
+            info!("Boss entering phase {phase}");
+            boss.phase = phase;
+            can_move.move_speed = boss.phases[phase].move_speed;
+        }
+    }
+}
+
+// Only the players can be caught - the boss still hunts an escorted person, but
+// reaching them doesn't settle the deal.
+fn track_boss_catch(
+    bosses: Query<(&GlobalTransform, &Danger), With<Boss>>,
+    players: Query<&GlobalTransform, With<Player>>,
+    mut encounter: ResMut<BossEncounter>,
+) {
+    for (boss, radius) in &bosses {
+        let boss = boss.translation();
+        if players
+            .iter()
+            .any(|player| player.translation().distance(boss) < radius.0)
+        {
+            encounter.caught = true;
+        }
+    }
+}
+
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct BossPhaseScorer {
+    pub phase: usize,
+}
+
+fn boss_phase_scorer_system(
+    bosses: Query<&Boss>,
+    mut query: Query<(&Actor, &mut Score, &BossPhaseScorer)>,
+) {
+    for (Actor(actor), mut score, scorer) in &mut query {
+        if let Ok(boss) = bosses.get(*actor) {
+            score.set(if boss.phase == scorer.phase { 1. } else { 0. });
+        }
+    }
+}
+
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct BossPhaseAction {
+    pub phase: usize,
+}
+
+fn boss_phase_action_system(
+    mut actors: Query<(&Actor, &mut ActionState, &BossPhaseAction, &ActionSpan)>,
+    mut bosses: Query<(&GlobalTransform, &mut Boss)>,
+    targets: Query<&GlobalTransform, With<DangerTarget>>,
+    mut commands: Commands,
+    mut rng: ResMut<GlobalRng>,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for (Actor(actor), mut state, action, span) in &mut actors {
+        let _guard = span.span().enter();
+
+        let Ok((position, mut boss)) = bosses.get_mut(*actor) else {
+            continue;
+        };
+        let position = position.translation().xy();
+
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+            }
+            ActionState::Cancelled => {
+                commands.entity(*actor).remove::<Moving>();
+                *state = ActionState::Failure;
+            }
+            ActionState::Executing => {
+                if boss.phase != action.phase {
+                    commands.entity(*actor).remove::<Moving>();
+                    *state = ActionState::Success;
+                    continue;
+                }
+                let Some(phase) = boss.phases.get(action.phase).cloned() else {
+                    *state = ActionState::Failure;
+                    continue;
+                };
+
+                let target = targets
+                    .iter()
+                    .map(|v| v.translation().xy())
+                    .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+
+                let direction = match phase.behavior {
+                    BossBehavior::Chase | BossBehavior::Summon => target
+                        .map(|target| target - position)
+                        .unwrap_or_default()
+                        .normalize_or_zero(),
+                    BossBehavior::Orbit => {
+                        let from_home = position - boss.home;
+                        let radial = (phase.orbit_radius - from_home.length()) / phase.orbit_radius;
+                        let from_home = from_home.normalize_or_zero();
+                        (from_home.perp() + from_home * radial.clamp(-1., 1.)).normalize_or_zero()
+                    }
+                };
+
+                if matches!(phase.behavior, BossBehavior::Summon)
+                    && now - boss.last_summon > phase.summon_interval
+                {
+                    boss.last_summon = now;
+                    let rng = rng.get_mut();
+                    let angle = rng.f32() * TAU;
+                    let offset = Vec2::new(angle.cos(), angle.sin()).mul(60.);
+                    let mut summoned = commands.spawn((
+                        phase.summon,
+                        SpatialBundle {
+                            transform: Transform::from_translation((position + offset).extend(0.)),
+                            ..Default::default()
+                        },
+                        DangerSpawner(*actor),
+                        InGame,
+                    ));
+                    if !danger_marker(phase.summon, &mut summoned) {
+                        summoned.despawn();
+                    }
+                }
+
+                commands.entity(*actor).insert(Moving(direction));
+            }
+            ActionState::Failure | ActionState::Success => {
+                commands.entity(*actor).remove::<Moving>();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct BossBar(Entity);
+
+#[derive(Component)]
+struct BossUi;
+
+fn setup_boss_ui(
+    bosses: Query<(Entity, &Name), Added<Boss>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    for (boss, name) in &bosses {
+        let mut bar = None;
+        let r = root(boss_bar_root, &asset_server, &mut commands, |p| {
+            text(name.as_str(), (), standard_text, p);
+            node(boss_bar_container, p, |p| {
+                bar = Some(node(boss_bar, p, |_| {}));
+            });
+        });
+        commands.entity(r).insert((InGame, BossUi, BossBar(boss)));
+        if let Some(bar) = bar {
+            commands.entity(bar).insert(BossBar(boss));
+        }
+    }
+}
+
+fn draw_boss_ui(
+    bosses: Query<(&Souls, &MaxSouls), With<Boss>>,
+    mut bars: Query<(Entity, &mut Style, &BossBar, Has<BossUi>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut style, bar, is_root) in &mut bars {
+        let Ok((souls, max_souls)) = bosses.get(bar.0) else {
+            if is_root {
+                commands.entity(entity).despawn_recursive();
+            }
+            continue;
+        };
+        if !is_root {
+            style.width = Val::Percent((souls.0 / max_souls.0).clamp(0., 1.) * 100.);
+        }
+    }
+}
@@ -1,19 +1,25 @@
 use std::collections::VecDeque;
 
 use bevy::prelude::*;
-use bevy_ui_dsl::{image, node, root};
+use bevy_ui_dsl::{image, node, root, text};
 use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use leafwing_input_manager::prelude::ActionState;
 
 use crate::{
     app_state::DrawDebugGizmos,
-    ui::classes::{
-        checkpoint_marker, checkpoint_marker_background, checkpoint_marker_content,
-        checkpoint_marker_empty, checkpoint_marker_inner_background, checkpoint_marker_root,
+    ui::{
+        classes::{
+            checkpoint_marker, checkpoint_marker_background, checkpoint_marker_content,
+            checkpoint_marker_empty, checkpoint_marker_inner_background, checkpoint_marker_root,
+            checkpoint_souls_text, standard_text,
+        },
+        intermediary_node_bundles::IntoIntermediaryNodeBundle,
     },
 };
 
 use super::{
+    actions::PlayerAction,
     player::Player,
     schedule::InGameUpdate,
     souls::{MaxSouls, Souls},
@@ -22,8 +28,11 @@ use super::{
 
 pub fn checkpoint_plugin(app: &mut ReloadableAppContents) {
     app.add_systems(Update, draw_checkpoint)
-        .add_systems(InGameUpdate, (collect_checkpoint, setup_checkpoint_ui))
-        .add_systems(PostUpdate, draw_checkpoint_ui);
+        .add_systems(
+            InGameUpdate,
+            (collect_checkpoint, cycle_checkpoint, setup_checkpoint_ui),
+        )
+        .add_systems(PostUpdate, (draw_checkpoint_ui, draw_stored_checkpoints));
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -31,12 +40,57 @@ pub struct StoredCheckpoint {
     pub position: Vec3,
     pub souls: Souls,
     pub max_souls: MaxSouls,
+    pub snapshot: Option<usize>,
 }
 
 #[derive(Component, Clone, Debug)]
 pub struct Checkpoints {
     pub checkpoints: VecDeque<StoredCheckpoint>,
     pub max_checkpoints: usize,
+    pub selected: usize,
+    pub used: usize,
+}
+
+impl Checkpoints {
+    pub fn new(max_checkpoints: usize) -> Self {
+        Self {
+            checkpoints: Default::default(),
+            max_checkpoints,
+            selected: 0,
+            used: 0,
+        }
+    }
+
+    pub fn store(&mut self, checkpoint: StoredCheckpoint) {
+        self.checkpoints.push_back(checkpoint);
+        if self.checkpoints.len() > self.max_checkpoints {
+            let _ = self.checkpoints.pop_front();
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn selected(&self) -> Option<&StoredCheckpoint> {
+        self.checkpoints
+            .get(self.selected)
+            .or_else(|| self.checkpoints.back())
+    }
+
+    pub fn take_selected(&mut self) -> Option<StoredCheckpoint> {
+        let index = self.selected.min(self.checkpoints.len().checked_sub(1)?);
+        let checkpoint = self.checkpoints.remove(index);
+        self.selected = index.min(self.checkpoints.len().saturating_sub(1));
+        self.used += 1;
+        checkpoint
+    }
+
+    pub fn cycle(&mut self) {
+        let len = self.checkpoints.len();
+        self.selected = if len == 0 {
+            0
+        } else {
+            (self.selected.min(len - 1) + 1) % len
+        };
+    }
 }
 
 #[derive(Component, Clone, Debug)]
@@ -62,6 +116,33 @@ fn draw_checkpoint(
     }
 }
 
+fn draw_stored_checkpoints(players: Query<&Checkpoints, With<Player>>, mut painter: ShapePainter) {
+    painter.hollow = true;
+    for checkpoints in &players {
+        let selected = checkpoints.selected().map(|v| v.position);
+        for checkpoint in checkpoints.checkpoints.iter() {
+            let is_selected = selected == Some(checkpoint.position);
+            painter.color = if is_selected {
+                crate::ui::colors::PRIMARY_COLOR
+            } else {
+                crate::ui::colors::PRIMARY_COLOR.with_a(0.3)
+            };
+            painter.set_translation(checkpoint.position);
+            painter.circle(if is_selected { 14. } else { 10. });
+        }
+    }
+}
+
+fn cycle_checkpoint(
+    mut players: Query<(&mut Checkpoints, &ActionState<PlayerAction>), With<Player>>,
+) {
+    for (mut checkpoints, actions) in &mut players {
+        if actions.just_pressed(PlayerAction::CycleCheckpoint) {
+            checkpoints.cycle();
+        }
+    }
+}
+
 fn collect_checkpoint(
     checkpoints: Query<(Entity, &GlobalTransform), With<Checkpoint>>,
     mut player: Query<(
@@ -80,14 +161,12 @@ fn collect_checkpoint(
             let distance = player_pos.distance(position);
             if distance < 20. {
                 commands.entity(checkpoint).despawn_recursive();
-                checkpoints.checkpoints.push_back(StoredCheckpoint {
+                checkpoints.store(StoredCheckpoint {
                     position: player_pos,
                     souls: *souls,
                     max_souls: *max_souls,
+                    snapshot: None,
                 });
-                if checkpoints.checkpoints.len() > checkpoints.max_checkpoints {
-                    let _ = checkpoints.checkpoints.pop_front();
-                }
                 collected.0 += 1;
                 break;
             }
@@ -101,6 +180,12 @@ struct CheckpointMarker(Entity, usize);
 #[derive(Component, Clone, Copy)]
 struct CheckpiontHeld(Entity, usize);
 
+#[derive(Component, Clone, Copy)]
+struct CheckpointSlot(Entity, usize);
+
+#[derive(Component, Clone, Copy)]
+struct CheckpointSoulsText(Entity, usize);
+
 fn setup_checkpoint_ui(
     player: Query<(Entity, &Checkpoints), With<Player>>,
     markers: Query<&CheckpointMarker>,
@@ -117,21 +202,42 @@ fn setup_checkpoint_ui(
     let r = root(checkpoint_marker_root, &asset_server, &mut commands, |p| {
         for (player, checkpoints) in player.iter() {
             for i in 0..checkpoints.max_checkpoints {
-                node(checkpoint_marker, p, |p| {
+                let mut checkpoint_held = None;
+                let mut checkpoint_percentage = None;
+                let mut souls = None;
+                let slot = node(checkpoint_marker, p, |p| {
                     image(checkpoint_marker_background, p);
-                    let checkpoint_held = image(checkpoint_marker_empty, p);
-                    let checkpoint_percentage = node(checkpoint_marker_content, p, |p| {
+                    checkpoint_held = Some(image(checkpoint_marker_empty, p));
+                    checkpoint_percentage = Some(node(checkpoint_marker_content, p, |p| {
                         image(checkpoint_marker_inner_background, p);
-                    });
-                    checkpoint_marekers.push((i, checkpoint_percentage, checkpoint_held, player))
+                    }));
+                    souls = Some(text("", checkpoint_souls_text.nb(), standard_text, p));
                 });
+                checkpoint_marekers.push((
+                    i,
+                    slot,
+                    checkpoint_percentage,
+                    checkpoint_held,
+                    souls,
+                    player,
+                ))
             }
         }
     });
 
-    for (i, marker, held, player) in checkpoint_marekers {
-        commands.entity(held).insert(CheckpiontHeld(player, i));
-        commands.entity(marker).insert(CheckpointMarker(player, i));
+    for (i, slot, marker, held, souls, player) in checkpoint_marekers {
+        commands.entity(slot).insert(CheckpointSlot(player, i));
+        if let Some(held) = held {
+            commands.entity(held).insert(CheckpiontHeld(player, i));
+        }
+        if let Some(marker) = marker {
+            commands.entity(marker).insert(CheckpointMarker(player, i));
+        }
+        if let Some(souls) = souls {
+            commands
+                .entity(souls)
+                .insert(CheckpointSoulsText(player, i));
+        }
     }
 
     commands.entity(r).insert(InGame);
@@ -140,8 +246,37 @@ fn setup_checkpoint_ui(
 fn draw_checkpoint_ui(
     mut markers: Query<(&CheckpointMarker, &mut Style), Without<CheckpiontHeld>>,
     mut holders: Query<(&CheckpiontHeld, &mut Style), Without<CheckpointMarker>>,
+    mut slots: Query<(&CheckpointSlot, &mut BackgroundColor)>,
+    mut souls_texts: Query<(&CheckpointSoulsText, &mut Text)>,
     players: Query<&Checkpoints>,
 ) {
+    for (CheckpointSlot(player, slot_index), mut background) in slots.iter_mut() {
+        let Ok(player) = players.get(*player) else {
+            continue;
+        };
+        let selected = !player.checkpoints.is_empty()
+            && player.selected.min(player.checkpoints.len() - 1) == *slot_index;
+        background.0 = if selected {
+            crate::ui::colors::PRIMARY_COLOR.with_a(0.4)
+        } else {
+            Color::NONE
+        };
+    }
+    for (CheckpointSoulsText(player, slot_index), mut text) in souls_texts.iter_mut() {
+        let Ok(player) = players.get(*player) else {
+            continue;
+        };
+        let value = player
+            .checkpoints
+            .get(*slot_index)
+            .map(|checkpoint| format!("{:.0}", checkpoint.souls.0))
+            .unwrap_or_default();
+        if let Some(section) = text.sections.first_mut() {
+            if section.value != value {
+                section.value = value;
+            }
+        }
+    }
     for (CheckpointMarker(player, marker_index), mut style) in markers.iter_mut() {
         let Ok(player) = players.get(*player) else {
             continue;
@@ -0,0 +1,240 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use super::{
+    checkpoints::{Checkpoints, StoredCheckpoint},
+    player::Player,
+    schedule::InGamePostUpdate,
+};
+
+pub fn co_op_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(InGamePostUpdate, sync_shared_checkpoints)
+        .add_systems(PostUpdate, draw_player_colors);
+}
+
+pub const MAX_PLAYERS: usize = 4;
+const PLAYER_SPACING: f32 = 40.;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CheckpointSharing {
+    #[default]
+    Individual,
+    Shared,
+}
+
+impl CheckpointSharing {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckpointSharing::Individual => "Checkpoints: Individual",
+            CheckpointSharing::Shared => "Checkpoints: Shared",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CheckpointSharing::Individual => CheckpointSharing::Shared,
+            CheckpointSharing::Shared => CheckpointSharing::Individual,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RitualRule {
+    #[default]
+    Any,
+    Majority,
+    All,
+}
+
+impl RitualRule {
+    pub fn satisfied(&self, arrived: usize, total: usize) -> bool {
+        match self {
+            RitualRule::Any => arrived > 0,
+            RitualRule::Majority => arrived * 2 > total,
+            RitualRule::All => total > 0 && arrived >= total,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RitualRule::Any => "Ritual: Anyone Arrives",
+            RitualRule::Majority => "Ritual: Most Arrive",
+            RitualRule::All => "Ritual: Everyone Arrives",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            RitualRule::Any => RitualRule::Majority,
+            RitualRule::Majority => RitualRule::All,
+            RitualRule::All => RitualRule::Any,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayStyle {
+    #[default]
+    CoOp,
+    Versus,
+}
+
+impl PlayStyle {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayStyle::CoOp => "Style: Co-op",
+            PlayStyle::Versus => "Style: Race",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            PlayStyle::CoOp => PlayStyle::Versus,
+            PlayStyle::Versus => PlayStyle::CoOp,
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct GameMode {
+    pub players: usize,
+    pub style: PlayStyle,
+    pub race_seed: u64,
+    pub checkpoints: CheckpointSharing,
+    pub ritual_rule: RitualRule,
+    pub save_points: bool,
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self {
+            players: 1,
+            style: PlayStyle::CoOp,
+            race_seed: 0,
+            checkpoints: CheckpointSharing::Individual,
+            ritual_rule: RitualRule::Any,
+            save_points: false,
+        }
+    }
+}
+
+impl GameMode {
+    pub fn is_co_op(&self) -> bool {
+        self.players > 1
+    }
+
+    pub fn is_versus(&self) -> bool {
+        self.is_co_op() && self.style == PlayStyle::Versus
+    }
+
+    pub fn players_label(&self) -> String {
+        format!("Players: {}", self.players)
+    }
+
+    pub fn save_points_label(&self) -> &'static str {
+        if self.save_points {
+            "Save Points: On"
+        } else {
+            "Save Points: Off"
+        }
+    }
+
+    pub fn next_player_count(&self) -> usize {
+        self.players % MAX_PLAYERS + 1
+    }
+
+    pub fn start_offset(&self, index: usize) -> Vec2 {
+        let center = (self.players as f32 - 1.) / 2.;
+        Vec2::Y * (index as f32 - center) * PLAYER_SPACING
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerIndex(pub usize);
+
+impl PlayerIndex {
+    pub fn color(&self) -> Color {
+        match self.0 % MAX_PLAYERS {
+            0 => crate::ui::colors::PRIMARY_COLOR,
+            1 => Color::rgb(0.35, 0.6, 1.),
+            2 => Color::rgb(0.45, 0.9, 0.45),
+            _ => Color::rgb(0.95, 0.8, 0.25),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Eliminated;
+
+fn same_checkpoint(a: &StoredCheckpoint, b: &StoredCheckpoint) -> bool {
+    a.position == b.position && a.souls.0 == b.souls.0 && a.max_souls.0 == b.max_souls.0
+}
+
+fn sync_shared_checkpoints(
+    mode: Res<GameMode>,
+    mut shared: Local<VecDeque<StoredCheckpoint>>,
+    mut players: ParamSet<(
+        Query<Ref<Checkpoints>, With<Player>>,
+        Query<&mut Checkpoints, With<Player>>,
+    )>,
+) {
+    if mode.checkpoints != CheckpointSharing::Shared || mode.is_versus() {
+        return;
+    }
+    let base = shared.clone();
+    let mut merged = base.clone();
+    let mut max_checkpoints = usize::MAX;
+    let mut changed = false;
+    for checkpoints in players.p0().iter().filter(|v| v.is_changed()) {
+        changed = true;
+        max_checkpoints = max_checkpoints.min(checkpoints.max_checkpoints);
+        let theirs = &checkpoints.checkpoints;
+        merged.retain(|v| {
+            theirs.iter().any(|t| same_checkpoint(t, v))
+                || !base.iter().any(|b| same_checkpoint(b, v))
+        });
+        for checkpoint in theirs.iter() {
+            match merged.iter_mut().find(|v| same_checkpoint(v, checkpoint)) {
+                Some(existing) => existing.snapshot = existing.snapshot.or(checkpoint.snapshot),
+                None => merged.push_back(*checkpoint),
+            }
+        }
+    }
+    if !changed {
+        return;
+    }
+    while merged.len() > max_checkpoints {
+        let _ = merged.pop_front();
+    }
+    for mut checkpoints in players.p1().iter_mut() {
+        let differs = checkpoints.checkpoints.len() != merged.len()
+            || checkpoints
+                .checkpoints
+                .iter()
+                .zip(merged.iter())
+                .any(|(a, b)| !same_checkpoint(a, b) || a.snapshot != b.snapshot);
+        if differs {
+            checkpoints.checkpoints = merged.clone();
+        }
+    }
+    *shared = merged;
+}
+
+fn draw_player_colors(
+    mode: Res<GameMode>,
+    players: Query<(&GlobalTransform, &PlayerIndex), With<Player>>,
+    mut painter: ShapePainter,
+) {
+    if !mode.is_co_op() {
+        return;
+    }
+    painter.hollow = true;
+    for (transform, index) in &players {
+        painter.color = index.color();
+        painter.set_translation(transform.translation());
+        painter.circle(16.);
+    }
+}
@@ -1,5 +1,7 @@
+use std::sync::Arc;
+
 use bevy::{
-    ecs::query::Has,
+    ecs::{query::Has, system::EntityCommands},
     math::Vec3Swizzles,
     prelude::*,
     utils::{HashMap, HashSet},
@@ -13,7 +15,7 @@ use big_brain::{
     thinker::{ActionSpan, Actor, HasThinker, Thinker},
 };
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::{AppState, DrawDebugGizmos},
@@ -25,15 +27,21 @@ use crate::{
 
 use super::{
     angelic_archers::angelic_archer_plugin,
+    banish::{banish_plugin, Stunned},
+    boss::boss_plugin,
     divine_detonator::divine_detonator_plugin,
+    escort::DangerTarget,
+    game_clock::GameClock,
     game_state::TemporaryIgnore,
     guardian_angel::guardian_angel_plugin,
     holy_hulk::{spawn_holy_hulk, HolyHulk},
+    lantern_bearer::{lantern_bearer_plugin, LanternBearer},
     lumbering_devil::spawn_lumbering_devil,
     movement::Moving,
     player::Player,
     schedule::{InGameActions, InGamePostUpdate, InGameScorers, InGameUpdate},
     souls::Death,
+    squads::{squad_plugin, SquadMember},
     stealthy_seraphim::{stealthy_seraphim_plugin, StealthySeraphim},
     InGame,
 };
@@ -45,6 +53,7 @@ pub fn danger_plugin(app: &mut ReloadableAppContents) {
             restless_scorer_system,
             chase_scorer_system,
             shoot_scorer_system,
+            patrol_scorer_system,
         ),
     )
     .add_systems(
@@ -54,6 +63,7 @@ pub fn danger_plugin(app: &mut ReloadableAppContents) {
             rest_action_system,
             chasing_action_system,
             shooting_action_system,
+            patrolling_action_system,
         ),
     )
     .add_systems(
@@ -75,6 +85,10 @@ pub fn danger_plugin(app: &mut ReloadableAppContents) {
     guardian_angel_plugin(app);
     angelic_archer_plugin(app);
     divine_detonator_plugin(app);
+    squad_plugin(app);
+    lantern_bearer_plugin(app);
+    banish_plugin(app);
+    boss_plugin(app);
 }
 
 #[derive(Component)]
@@ -82,7 +96,7 @@ pub struct Danger(pub f32);
 #[derive(Component)]
 pub struct DangerSpawner(pub Entity);
 
-#[derive(Component, Clone, Copy, Debug, Reflect, Deserialize, InspectorOptions)]
+#[derive(Component, Clone, Copy, Debug, Reflect, Deserialize, Serialize, InspectorOptions)]
 pub enum DangerType {
     HolyHulk,
     StealthySeraphim,
@@ -90,6 +104,7 @@ pub enum DangerType {
     AngelicArcher,
     DivineDetonator,
     LumberingDevil,
+    LanternBearer,
 }
 
 #[derive(Component)]
@@ -157,7 +172,7 @@ fn setup_danger_in_grid(
 fn mark_teleported_danger(
     dangers: Query<Entity, (With<TemporaryIgnore>, With<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
@@ -168,7 +183,7 @@ fn despawn_danger(
     dangers: Query<(Entity, &Transform, &SpawnTime, &DangerSpawner), Without<TemporaryIgnore>>,
     player: Query<&GlobalTransform, With<Player>>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     let positions = player.iter().map(|v| v.translation()).collect::<Box<[_]>>();
@@ -189,7 +204,10 @@ fn despawn_danger(
 }
 
 pub fn spawn_danger(
-    dangers: Query<Entity, (With<DangerAwaits>, Without<DangerExists>)>,
+    dangers: Query<
+        (Entity, Option<&PatrolRoute>, Option<&SquadMember>),
+        (With<DangerAwaits>, Without<DangerExists>),
+    >,
     mut commands: Commands,
     grid: Res<CollisionGrid>,
     player: Query<&GlobalTransform, With<Player>>,
@@ -216,7 +234,7 @@ pub fn spawn_danger(
             continue;
         };
         for DangerInGrid(danger, position, danger_type) in grid_cell.iter() {
-            let Ok(danger) = dangers.get(*danger) else {
+            let Ok((danger, patrol_route, squad_member)) = dangers.get(*danger) else {
                 continue;
             };
             info!("Found danger without danger awaits");
@@ -235,32 +253,48 @@ pub fn spawn_danger(
                 DangerSpawner(danger),
                 InGame,
             ));
-            match danger_type {
-                DangerType::HolyHulk => {
-                    child.insert(HolyHulk);
-                }
-                DangerType::StealthySeraphim => {
-                    child.insert(StealthySeraphim);
-                }
-                DangerType::GuardianAngel => {
-                    error!("Shouldn't get here");
-                    child.despawn();
-                    commands.entity(danger).despawn();
-                }
-                DangerType::AngelicArcher => {
-                    child.insert(AngelicArcher);
-                }
-                DangerType::DivineDetonator => {
-                    child.insert(DivineDetonator);
-                }
-                DangerType::LumberingDevil => {
-                    child.insert(LumberingDevil);
-                }
-            };
+            if let Some(patrol_route) = patrol_route {
+                child.insert(patrol_route.clone());
+            }
+            if let Some(squad_member) = squad_member {
+                child.insert(*squad_member);
+            }
+            if !danger_marker(*danger_type, &mut child) {
+                error!("Shouldn't get here");
+                child.despawn();
+                commands.entity(danger).despawn();
+            }
         }
     }
 }
 
+pub fn danger_marker(danger_type: DangerType, danger: &mut EntityCommands) -> bool {
+    match danger_type {
+        DangerType::HolyHulk => {
+            danger.insert(HolyHulk);
+        }
+        DangerType::StealthySeraphim => {
+            danger.insert(StealthySeraphim);
+        }
+        DangerType::GuardianAngel => {
+            return false;
+        }
+        DangerType::AngelicArcher => {
+            danger.insert(AngelicArcher);
+        }
+        DangerType::DivineDetonator => {
+            danger.insert(DivineDetonator);
+        }
+        DangerType::LumberingDevil => {
+            danger.insert(LumberingDevil);
+        }
+        DangerType::LanternBearer => {
+            danger.insert(LanternBearer);
+        }
+    };
+    true
+}
+
 fn draw_danger(
     dangers: Query<(&GlobalTransform, &HasThinker, &Danger, &Restlessness)>,
     dangers_await: Query<(&GlobalTransform, &DangerAwaits)>,
@@ -291,6 +325,7 @@ fn draw_danger(
             "Resting" => Color::BLUE,
             "Chasing" => Color::ORANGE,
             "Meandering" => Color::WHITE,
+            "Patrolling" => Color::GREEN,
             _ => Color::BLACK,
         };
         painter.circle(danger.0 / 2.);
@@ -312,7 +347,7 @@ pub struct Restlessness {
 }
 
 fn restlessness_system(
-    time: Res<Time>,
+    time: Res<GameClock>,
     mut restlessness: Query<&mut Restlessness, Without<TemporaryIgnore>>,
 ) {
     let delta = time.delta_seconds();
@@ -327,7 +362,7 @@ pub struct Meandering {
 }
 
 fn meandering_action_system(
-    time: Res<Time>,
+    time: Res<GameClock>,
     mut restless: Query<&mut Restlessness>,
     mut actors: Query<(&Actor, &mut ActionState, &Meandering, &ActionSpan)>,
     mut commands: Commands,
@@ -417,9 +452,9 @@ pub struct Chasing {
 fn chasing_action_system(
     mut actors: Query<(&Actor, &mut ActionState, &mut Chasing)>,
     mut chaser: Query<(&GlobalTransform, Option<&mut Restlessness>), With<Danger>>,
-    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    players: Query<(Entity, &GlobalTransform), With<DangerTarget>>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
     _death: EventWriter<Death>,
 ) {
     let delta = time.delta_seconds();
@@ -511,7 +546,7 @@ pub struct Chase {
 
 fn chase_scorer_system(
     dangers: Query<&GlobalTransform, With<Danger>>,
-    players: Query<&GlobalTransform, With<Player>>,
+    players: Query<&GlobalTransform, With<DangerTarget>>,
     mut query: Query<(&Actor, &mut Score, &Chase)>,
 ) {
     for (Actor(actor), mut score, chase) in &mut query {
@@ -543,14 +578,14 @@ pub struct Shooting {
 
 fn shooting_action_system(
     mut actors: Query<(&Actor, &mut ActionState, &mut Shooting)>,
-    mut shooter: Query<(&GlobalTransform, Has<Shot>), With<Danger>>,
-    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut shooter: Query<(&GlobalTransform, Has<Shot>, Has<Stunned>), With<Danger>>,
+    players: Query<(Entity, &GlobalTransform), With<DangerTarget>>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for (Actor(actor), mut state, mut shooting) in &mut actors {
-        let Ok((position, has_shot)) = shooter.get_mut(*actor) else {
+        let Ok((position, has_shot, stunned)) = shooter.get_mut(*actor) else {
             continue;
         };
         let position = position.translation();
@@ -563,7 +598,7 @@ fn shooting_action_system(
                 *state = ActionState::Failure;
             }
             ActionState::Executing => {
-                if has_shot {
+                if has_shot || stunned {
                     continue;
                 }
                 let player = if let Some(player) = shooting.player {
@@ -637,7 +672,7 @@ pub struct Shoot {
 
 fn shoot_scorer_system(
     dangers: Query<(&GlobalTransform, Has<Shot>), With<Danger>>,
-    players: Query<&GlobalTransform, With<Player>>,
+    players: Query<&GlobalTransform, With<DangerTarget>>,
     mut query: Query<(&Actor, &mut Score, &Shoot)>,
 ) {
     for (Actor(actor), mut score, shoot) in &mut query {
@@ -665,3 +700,109 @@ fn shoot_scorer_system(
         }
     }
 }
+
+pub const PATROL_WAYPOINT_DISTANCE: f32 = 15.;
+
+#[derive(Component, Clone, Debug)]
+pub struct PatrolRoute {
+    pub points: Arc<[Vec2]>,
+    pub looping: bool,
+    pub next: usize,
+    pub forward: bool,
+}
+
+impl PatrolRoute {
+    pub fn new(points: impl Into<Arc<[Vec2]>>, looping: bool) -> Self {
+        Self {
+            points: points.into(),
+            looping,
+            next: 0,
+            forward: true,
+        }
+    }
+
+    fn advance(&mut self) {
+        let len = self.points.len();
+        if len < 2 {
+            return;
+        }
+        if self.looping {
+            self.next = (self.next + 1) % len;
+        } else if self.forward {
+            if self.next + 1 >= len {
+                self.forward = false;
+                self.next -= 1;
+            } else {
+                self.next += 1;
+            }
+        } else if self.next == 0 {
+            self.forward = true;
+            self.next = 1;
+        } else {
+            self.next -= 1;
+        }
+    }
+}
+
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct Patrol;
+
+fn patrol_scorer_system(
+    routes: Query<(), With<PatrolRoute>>,
+    mut query: Query<(&Actor, &mut Score), With<Patrol>>,
+) {
+    for (Actor(actor), mut score) in &mut query {
+        score.set(if routes.contains(*actor) { 0.9 } else { 0. });
+    }
+}
+
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct Patrolling;
+
+fn patrolling_action_system(
+    mut actors: Query<(&Actor, &mut ActionState, &Patrolling, &ActionSpan)>,
+    mut patrollers: Query<(&GlobalTransform, &mut PatrolRoute)>,
+    mut commands: Commands,
+) {
+    for (Actor(actor), mut state, _, span) in &mut actors {
+        let _guard = span.span().enter();
+
+        let Ok((position, mut route)) = patrollers.get_mut(*actor) else {
+            if matches!(*state, ActionState::Requested | ActionState::Executing) {
+                *state = ActionState::Failure;
+            }
+            continue;
+        };
+
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+            }
+            ActionState::Cancelled => {
+                commands.entity(*actor).remove::<Moving>();
+                *state = ActionState::Failure;
+            }
+            ActionState::Executing => {
+                let position = position.translation().xy();
+                let Some(target) = route.points.get(route.next).copied() else {
+                    *state = ActionState::Failure;
+                    continue;
+                };
+                let direction = target - position;
+                if direction.length() < PATROL_WAYPOINT_DISTANCE {
+                    route.advance();
+                }
+                commands
+                    .entity(*actor)
+                    .insert(Moving(direction.normalize_or_zero()));
+            }
+            ActionState::Failure => {
+                commands.entity(*actor).remove::<Moving>();
+            }
+            ActionState::Success => {
+                commands.entity(*actor).remove::<Moving>();
+            }
+            _ => {}
+        }
+    }
+}
@@ -11,10 +11,13 @@ use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 use crate::assets::WithMesh;
 
 use super::{
-    danger::{Danger, Resting, Restlessness, Shoot, Shooting, Shot, SpawnTime},
+    danger::{Danger, Patrol, Patrolling, Resting, Restlessness, Shoot, Shooting, Shot, SpawnTime},
+    game_clock::GameClock,
     movement::{CanMove, Moving},
     schedule::InGameUpdate,
+    slow_motion::{SlowMotionPulse, EXPLOSION_PULSE},
     souls::LethalTouch,
+    teleport::PathInteraction,
 };
 
 #[derive(Component)]
@@ -30,13 +33,14 @@ pub fn divine_detonator_plugin(app: &mut ReloadableAppContents) {
 fn spawn_divine_detonator(
     dangers: Query<Entity, (With<DivineDetonator>, Without<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
         commands.entity(danger).insert((
             Name::new("Divine Detonator"),
             Danger(10.),
+            PathInteraction::Block,
             CanMove { move_speed: 200. },
             SpawnTime(now),
             Restlessness {
@@ -60,6 +64,7 @@ fn spawn_divine_detonator(
                         shot_speed: 6.,
                     },
                 )
+                .when(Patrol, Patrolling)
                 .otherwise(Resting),
             WithMesh::DivineDetonator,
         ));
@@ -144,6 +149,7 @@ const EXPLOSION_DONE: u64 = 1212443;
 fn clear_teleport(
     teleporters: Query<Entity, With<IsShot>>,
     mut event: EventReader<TweenCompleted>,
+    mut pulses: EventWriter<SlowMotionPulse>,
     mut commands: Commands,
 ) {
     for event in event.iter() {
@@ -157,7 +163,12 @@ fn clear_teleport(
                     .entity(teleporter)
                     .insert(Danger(80.))
                     .despawn_descendants()
-                    .insert((WithMesh::DivineDetonatorExplosion, LethalTouch));
+                    .insert((
+                        WithMesh::DivineDetonatorExplosion,
+                        LethalTouch,
+                        PathInteraction::Kill,
+                    ));
+                pulses.send(SlowMotionPulse(EXPLOSION_PULSE));
             }
         }
     }
@@ -0,0 +1,211 @@
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use leafwing_input_manager::prelude::ActionState;
+use serde::Deserialize;
+
+use super::{
+    actions::PlayerAction,
+    danger::Danger,
+    game_clock::GameClock,
+    game_state::{GameState, TemporaryIgnore},
+    guardian_angel::GuardianAngel,
+    player::{DiedOf, Player},
+    ritual::Person,
+    schedule::{InGamePostUpdate, InGameUpdate},
+    shadow::{CheckForShadow, InShadow},
+    souls::{Damage, DamageType, LethalTouch, Souls},
+    CurrentLevel,
+};
+
+pub fn escort_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(
+        InGameUpdate,
+        (setup_escort, cloak_person, hide_person, damage_person).chain(),
+    )
+    .add_systems(
+        InGamePostUpdate,
+        person_lost.run_if(in_state(GameState::InGame)),
+    )
+    .add_systems(PostUpdate, draw_person_health);
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(default)]
+pub struct EscortSettings {
+    pub health: f32,
+    pub contact_damage: f32,
+    pub cloak_range: f32,
+    pub cloak_duration: f32,
+    pub cloak_cost: f32,
+}
+
+impl Default for EscortSettings {
+    fn default() -> Self {
+        Self {
+            health: 100.,
+            contact_damage: 40.,
+            cloak_range: 80.,
+            cloak_duration: 4.,
+            cloak_cost: 10.,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DangerTarget;
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PersonHealth {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+#[component(storage = "SparseSet")]
+struct Cloaked(f32);
+
+fn setup_escort(
+    people: Query<Entity, (With<Person>, Without<PersonHealth>)>,
+    level: Res<CurrentLevel>,
+    mut commands: Commands,
+) {
+    let Some(settings) = &level.escort else {
+        return;
+    };
+    for person in &people {
+        commands.entity(person).insert((
+            PersonHealth {
+                current: settings.health,
+                max: settings.health,
+            },
+            CheckForShadow,
+            DangerTarget,
+        ));
+    }
+}
+
+fn cloak_person(
+    players: Query<(Entity, &GlobalTransform, &ActionState<PlayerAction>, &Souls), With<Player>>,
+    mut people: Query<(Entity, &GlobalTransform, Option<&mut Cloaked>), With<PersonHealth>>,
+    level: Res<CurrentLevel>,
+    mut damage: EventWriter<Damage>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let Some(settings) = &level.escort else {
+        return;
+    };
+    for (person, position, cloaked) in &mut people {
+        if let Some(mut cloaked) = cloaked {
+            cloaked.0 -= time.delta_seconds();
+            if cloaked.0 <= 0. {
+                commands.entity(person).remove::<Cloaked>();
+            }
+            continue;
+        }
+        let position = position.translation();
+        for (player, transform, actions, souls) in &players {
+            if !actions.just_pressed(PlayerAction::Cloak)
+                || transform.translation().distance(position) > settings.cloak_range
+                || souls.0 <= settings.cloak_cost
+            {
+                continue;
+            }
+            damage.send(Damage {
+                entity: player,
+                amount: settings.cloak_cost,
+                damage_type: DamageType::Cloak,
+            });
+            commands
+                .entity(person)
+                .insert(Cloaked(settings.cloak_duration));
+            break;
+        }
+    }
+}
+
+fn hide_person(
+    people: Query<(Entity, Has<InShadow>, Has<Cloaked>, Has<DangerTarget>), With<PersonHealth>>,
+    mut commands: Commands,
+) {
+    for (person, in_shadow, cloaked, targeted) in &people {
+        let hidden = in_shadow || cloaked;
+        if hidden && targeted {
+            commands.entity(person).remove::<DangerTarget>();
+        } else if !hidden && !targeted {
+            commands.entity(person).insert(DangerTarget);
+        }
+    }
+}
+
+fn damage_person(
+    mut people: Query<(&GlobalTransform, &mut PersonHealth), With<DangerTarget>>,
+    dangers: Query<
+        (&GlobalTransform, &Danger),
+        (
+            With<LethalTouch>,
+            Without<TemporaryIgnore>,
+            Without<GuardianAngel>,
+        ),
+    >,
+    level: Res<CurrentLevel>,
+    time: Res<GameClock>,
+) {
+    let Some(settings) = &level.escort else {
+        return;
+    };
+    for (position, mut health) in &mut people {
+        let position = position.translation();
+        for (danger, radius) in &dangers {
+            if position.distance(danger.translation()) < radius.0 {
+                health.current -= settings.contact_damage * time.delta_seconds();
+            }
+        }
+    }
+}
+
+fn person_lost(
+    people: Query<&PersonHealth>,
+    players: Query<Entity, With<Player>>,
+    mut commands: Commands,
+) {
+    if !people.iter().any(|health| health.current <= 0.) {
+        return;
+    }
+    for player in &players {
+        commands
+            .entity(player)
+            .insert(DiedOf(DamageType::PersonLost));
+    }
+    commands.insert_resource(NextState(Some(GameState::Failed)));
+}
+
+fn draw_person_health(
+    people: Query<(
+        &GlobalTransform,
+        &PersonHealth,
+        Has<Cloaked>,
+        Has<DangerTarget>,
+    )>,
+    mut painter: ShapePainter,
+) {
+    for (position, health, cloaked, targeted) in &people {
+        painter.set_translation(position.translation() + Vec3::Z * 2.);
+        painter.hollow = true;
+        painter.thickness = 3.;
+        painter.color = if targeted {
+            crate::ui::colors::BAD_COLOR
+        } else {
+            crate::ui::colors::PRIMARY_COLOR
+        };
+        let ratio = (health.current / health.max).clamp(0., 1.);
+        painter.arc(25., 0., ratio * std::f32::consts::TAU);
+        if cloaked {
+            painter.color = crate::ui::colors::PRIMARY_COLOR.with_a(0.3);
+            painter.hollow = false;
+            painter.circle(30.);
+        }
+    }
+}
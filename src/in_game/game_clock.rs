@@ -0,0 +1,92 @@
+use bevy::{ecs::query::Has, prelude::*};
+use bevy_tweening::{Animator, AnimatorState};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use crate::app_state::AppState;
+
+use super::game_state::{GameState, PauseState};
+
+pub fn game_clock_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_game_clock)
+        .add_systems(
+            First,
+            (tick_game_clock, sync_animators)
+                .chain()
+                .run_if(in_state(AppState::InGame)),
+        );
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GameClock {
+    elapsed: f32,
+    delta: f32,
+    running: bool,
+    pub scale: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.,
+            delta: 0.,
+            running: false,
+            scale: 1.,
+        }
+    }
+}
+
+impl GameClock {
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+}
+
+fn reset_game_clock(mut commands: Commands) {
+    commands.insert_resource(GameClock::default());
+}
+
+fn tick_game_clock(
+    mut clock: ResMut<GameClock>,
+    time: Res<Time>,
+    pause: Res<State<PauseState>>,
+    game: Res<State<GameState>>,
+) {
+    clock.running = *pause.get() == PauseState::None && *game.get() == GameState::InGame;
+    clock.delta = if clock.running {
+        time.delta_seconds() * clock.scale.max(0.)
+    } else {
+        0.
+    };
+    clock.elapsed += clock.delta;
+}
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct PausedByClock;
+
+fn sync_animators(
+    clock: Res<GameClock>,
+    mut animators: Query<(Entity, &mut Animator<Transform>, Has<PausedByClock>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut animator, paused_by_clock) in &mut animators {
+        if clock.is_running() {
+            if paused_by_clock {
+                animator.state = AnimatorState::Playing;
+                commands.entity(entity).remove::<PausedByClock>();
+            }
+            animator.set_speed(clock.scale);
+        } else if animator.state == AnimatorState::Playing {
+            animator.state = AnimatorState::Paused;
+            commands.entity(entity).insert(PausedByClock);
+        }
+    }
+}
@@ -5,6 +5,7 @@ use bevy_ui_dsl::*;
 use crate::{
     app_state::AppState,
     assets::WithMesh,
+    persistence,
     ui::{
         buttons::{focus_text_button, focused_button_activated, TypedFocusedButtonQuery},
         classes::*,
@@ -15,8 +16,14 @@ use crate::{
 };
 
 use super::{
-    checkpoints::CheckpointCollected, game_state::GameState,
-    player::CheckpointsConsumedForTeleport, InGame, Levels, TrackingCamera,
+    checkpoints::CheckpointCollected,
+    co_op::{Eliminated, GameMode},
+    game_state::GameState,
+    player::CheckpointsConsumedForTeleport,
+    progression::{Progression, PROGRESSION_FILE},
+    ritual_minigame::RitualPerformance,
+    versus::RaceResults,
+    InGame, Levels, TrackingCamera,
 };
 use dexterous_developer::{
     dexterous_developer_setup, ReloadableApp, ReloadableAppContents, ReloadableElementsSetup,
@@ -32,6 +39,7 @@ impl Plugin for GameCompletedPlugin {
 #[dexterous_developer_setup(game_completed)]
 fn reloadable(app: &mut ReloadableAppContents) {
     app.reset_setup_in_state::<Screen, _, _>(GameState::Complete, setup)
+        .add_systems(OnEnter(GameState::Complete), bank_score)
         .add_systems(
             Update,
             (
@@ -54,27 +62,50 @@ enum Button {
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    players: Query<(&CheckpointCollected, &CheckpointsConsumedForTeleport)>,
+    players: Query<(&CheckpointCollected, &CheckpointsConsumedForTeleport), Without<Eliminated>>,
     in_game: Query<Entity, With<InGame>>,
     mut camera: Query<&mut Transform, With<TrackingCamera>>,
     levels: Res<Levels>,
     current_level_id: Res<CurrentLevelID>,
+    mode: Res<GameMode>,
+    results: Res<RaceResults>,
+    performance: Res<RitualPerformance>,
 ) {
     commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
+    let ranking = mode.is_versus().then(|| results.ranking());
     let mut menu_button = None;
     let mut level_button = None;
     let r = root(c_root, &asset_server, &mut commands, |p| {
         node(primary_box, p, |p| {
             node((span.nb(), primary_box_main.nb()), p, |p| {
-                text("You got the Deal!", (), main_text, p);
+                let title = match ranking.as_ref().and_then(|ranking| ranking.first()) {
+                    Some((winner, _)) => format!("Player {} got the Deal!", winner + 1),
+                    None => "You got the Deal!".to_string(),
+                };
+                text(title, (), main_text, p);
             });
 
-            for (collected, consumed) in &players {
+            for (place, (player, time)) in ranking.iter().flatten().enumerate() {
+                let result = match time {
+                    Some(time) => format!("{:.1}s", time),
+                    None => "Didn't make it".to_string(),
+                };
+                node((span.nb(), primary_box_item.nb()), p, |p| {
+                    text(
+                        format!("{}. Player {} - {result}", place + 1, player + 1),
+                        (),
+                        standard_text,
+                        p,
+                    );
+                });
+            }
+
+            for (collected, consumed) in players.iter().filter(|_| ranking.is_none()) {
+                let score = player_score(collected, consumed, &performance);
                 let collected = collected.0;
                 let consumed = consumed.0;
                 let collected_score = 10 * collected;
                 let consumed_score = 5 * consumed;
-                let score = collected_score + consumed_score;
 
                 node((span.nb(), primary_box_item.nb()), p, |p| {
                     text(
@@ -93,6 +124,19 @@ fn setup(
                     );
                 });
 
+                if performance.mode.is_some() {
+                    let quality = (performance.quality() * 100.).round();
+                    let bonus = performance.bonus();
+                    node((span.nb(), primary_box_item.nb()), p, |p| {
+                        text(
+                            format!("Ritual: {quality}% = {bonus}"),
+                            (),
+                            standard_text,
+                            p,
+                        );
+                    });
+                }
+
                 node((span.nb(), primary_box_item.nb()), p, |p| {
                     text(format!("Score: {score}!"), (), main_text, p);
                 });
@@ -144,6 +188,30 @@ fn setup(
     ));
 }
 
+fn player_score(
+    collected: &CheckpointCollected,
+    consumed: &CheckpointsConsumedForTeleport,
+    performance: &RitualPerformance,
+) -> usize {
+    10 * collected.0 + 5 * consumed.0 + performance.bonus()
+}
+
+fn bank_score(
+    players: Query<(&CheckpointCollected, &CheckpointsConsumedForTeleport), Without<Eliminated>>,
+    performance: Res<RitualPerformance>,
+    mode: Res<GameMode>,
+    mut progression: ResMut<Progression>,
+) {
+    if mode.is_versus() {
+        return;
+    }
+    progression.score += players
+        .iter()
+        .map(|(collected, consumed)| player_score(collected, consumed, &performance))
+        .sum::<usize>();
+    persistence::save(PROGRESSION_FILE, progression.as_ref());
+}
+
 fn process_input(
     In(focused): In<Option<Entity>>,
     mut commands: Commands,
@@ -17,8 +17,8 @@ use dexterous_developer::{
 };
 
 use super::{
-    danger::DangerType, game_state::GameState, player::DiedOf, souls::DamageType, InGame,
-    TrackingCamera,
+    boss::BossDefinition, danger::DangerType, game_state::GameState, player::DiedOf,
+    souls::DamageType, CurrentLevel, InGame, TrackingCamera,
 };
 pub struct GameOverPlugin;
 
@@ -52,8 +52,15 @@ fn setup(
     players: Query<&DiedOf>,
     in_game: Query<Entity, With<InGame>>,
     mut camera: Query<&mut Transform, With<TrackingCamera>>,
+    level: Res<CurrentLevel>,
 ) {
     commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
+    let boss_name = level
+        .boss
+        .as_ref()
+        .map(|boss| boss.name.clone())
+        .unwrap_or_else(|| BossDefinition::default().name);
+    let boss_denied = format!("{boss_name} denied your deal");
     let mut menu_button = None;
     let r = root(c_root, &asset_server, &mut commands, |p| {
         node(primary_box, p, |p| {
@@ -72,8 +79,19 @@ fn setup(
                                 DangerType::AngelicArcher => "Abolished by Angelic Archers",
                                 DangerType::DivineDetonator => "Demolished by Divine Detonator",
                                 DangerType::LumberingDevil => "Dunked by a Devil",
+                                DangerType::LanternBearer => "Lit up by a Lantern Bearer",
                             },
                             DamageType::TimeOut => "You didn't reach the summoning on time",
+                            DamageType::Boss => boss_denied.as_str(),
+                            DamageType::ObjectiveFailed => {
+                                "You didn't hold up your end of the deal"
+                            }
+                            DamageType::PersonLost => "The one you were guiding never made it",
+                            DamageType::RitualFailed => {
+                                "The pentagram broke before the deal was sealed"
+                            }
+                            DamageType::SlowMotion => "You spent your soul holding back time",
+                            DamageType::Cloak => "You gave your soul to hide another",
                         },
                         (),
                         standard_text,
@@ -112,7 +130,7 @@ fn setup(
         WithMesh::PlayerDead,
     ));
 
-    let Ok(player) = players.get_single() else {
+    let Some(player) = players.iter().next() else {
         return;
     };
     let mesh = match &player.0 {
@@ -124,8 +142,15 @@ fn setup(
             DangerType::AngelicArcher => WithMesh::AngelicArchersFace,
             DangerType::DivineDetonator => WithMesh::DivineDetonatorFace,
             DangerType::LumberingDevil => WithMesh::DevilFace,
+            DangerType::LanternBearer => WithMesh::GuardianAngelFace,
         },
         DamageType::TimeOut => WithMesh::PentagramFail,
+        DamageType::Boss => WithMesh::GuardianAngelFace,
+        DamageType::ObjectiveFailed => WithMesh::PentagramFail,
+        DamageType::PersonLost => WithMesh::PentagramFail,
+        DamageType::RitualFailed => WithMesh::PentagramFail,
+        DamageType::SlowMotion => WithMesh::PentagramFail,
+        DamageType::Cloak => WithMesh::PentagramFail,
     };
     commands.spawn((
         Screen,
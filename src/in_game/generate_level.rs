@@ -9,7 +9,7 @@ use bevy_inspector_egui::{prelude::ReflectInspectorOptions, InspectorOptions};
 use bevy_turborand::{rng::Rng, DelegatedRng, GlobalRng, TurboRand};
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 use noisy_bevy::simplex_noise_2d_seeded;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
@@ -18,8 +18,25 @@ use crate::{
 };
 
 use super::{
-    checkpoints::Checkpoint, danger::DangerType, game_state::GameState, movement::CanMove,
-    player::ConstructPlayer, ritual::Person, shadow::Shadow, InGame,
+    beat::{LevelSong, TempoSettings},
+    boss::BossDefinition,
+    checkpoints::Checkpoint,
+    co_op::{GameMode, PlayerIndex},
+    danger::{DangerType, PatrolRoute},
+    escort::EscortSettings,
+    game_state::GameState,
+    movement::CanMove,
+    objectives::{Objective, ObjectiveMode},
+    player::ConstructPlayer,
+    ritual::Person,
+    ritual_minigame::RitualMinigame,
+    road_curve::RoadCurve,
+    shadow::Shadow,
+    squads::{squad_offsets, SquadBlackboard, SquadMember},
+    teleport::TeleportEnergySettings,
+    timeline::{LevelAction, TimelineEvent},
+    triggers::{spawn_triggers, TriggerDefinition},
+    InGame,
 };
 
 pub fn level_generate_plugin(app: &mut ReloadableAppContents) {
@@ -51,11 +68,21 @@ pub struct CurrentLevel {
 
     pub initial_text: Vec<String>,
     pub timed_text: Vec<(f32, f32, String)>,
+    pub timeline: Vec<TimelineEvent>,
+    pub triggers: Vec<TriggerDefinition>,
+    pub objectives: Vec<Objective>,
+    pub objective_mode: ObjectiveMode,
     pub locale: Locale,
 
     pub person: Option<String>,
     pub guardian: Option<String>,
 
+    pub boss: Option<BossDefinition>,
+    pub teleport_energy: Option<TeleportEnergySettings>,
+    pub escort: Option<EscortSettings>,
+    pub ritual_minigame: Option<RitualMinigame>,
+    pub tempo: Option<TempoSettings>,
+
     #[serde(skip)]
     pub song_handle: Option<Handle<AudioSource>>,
     #[serde(skip)]
@@ -74,9 +101,18 @@ impl Default for CurrentLevel {
             person_handle: None,
             guardian_handle: None,
             guardian: None,
+            boss: None,
+            teleport_energy: None,
+            escort: None,
+            ritual_minigame: None,
+            tempo: None,
             locale: Locale::Forest,
             initial_text: vec![],
             timed_text: vec![],
+            timeline: vec![],
+            triggers: vec![],
+            objectives: vec![],
+            objective_mode: ObjectiveMode::All,
             song_length: 60.,
             curviness: 120.,
             segments: vec![
@@ -125,7 +161,7 @@ impl Default for CurrentLevel {
     }
 }
 
-#[derive(Clone, Reflect, InspectorOptions, Deserialize)]
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Serialize)]
 #[serde(default)]
 pub struct Segment {
     pub tree_density: f32,
@@ -135,6 +171,10 @@ pub struct Segment {
     pub decor_density: f32,
     pub danger_densities: Vec<(DangerType, f32)>,
     pub split_levels: u8,
+    pub road_patrol_chance: f32,
+    pub loop_patrol_chance: f32,
+    pub seraphim_squad_size: usize,
+    pub on_enter: Vec<LevelAction>,
 }
 
 impl Default for Segment {
@@ -147,6 +187,10 @@ impl Default for Segment {
             tree_variation: 0.2,
             checkpoint_variation: 0.2,
             decor_density: 0.7,
+            road_patrol_chance: 0.,
+            loop_patrol_chance: 0.,
+            seraphim_squad_size: 1,
+            on_enter: vec![],
         }
     }
 }
@@ -156,9 +200,14 @@ fn spawn_level(
     asset_server: Res<AssetServer>,
     mut rng: ResMut<GlobalRng>,
     level: Res<CurrentLevel>,
+    mode: Res<GameMode>,
 ) {
     info!("Rebuilding Level");
-    let rng = rng.get_mut();
+    let mut race_rng = mode.is_versus().then(|| Rng::with_seed(mode.race_seed));
+    let rng = match race_rng.as_mut() {
+        Some(race_rng) => race_rng,
+        None => rng.get_mut(),
+    };
     commands.insert_resource(ClearColor(level.locale.bg_color()));
     commands.insert_resource(level.locale);
     let source = if let Some(handle) = &level.song_handle {
@@ -190,6 +239,7 @@ fn spawn_level(
                 ..Default::default()
             },
         },
+        LevelSong,
         InGame,
     ));
 
@@ -207,14 +257,14 @@ fn spawn_level(
         .with_children(|p| {
             p.spawn((SpatialBundle::default(), Name::new("Road")))
                 .with_children(|p| {
-                    for road in level_shapes.roads.iter() {
-                        spawn_road_segment(p, road, rng);
+                    for road in level_shapes.road_curves.iter() {
+                        spawn_road_curve(p, road, rng);
                     }
                 });
 
             for (index, section) in level_shapes.section.iter().enumerate() {
                 let segment = level.segments.get(index).cloned().unwrap_or_default();
-                fill_section(p, section, rng, &segment);
+                fill_section(p, section, rng, &segment, &level_shapes.roads);
             }
 
             p.spawn((
@@ -226,23 +276,27 @@ fn spawn_level(
                 },
                 Person(
                     level.song_length,
-                    level_shapes.target_path.clone(),
+                    level_shapes.route.clone(),
                     person.clone(),
                     guardian.clone(),
                 ),
             ));
 
-            p.spawn((
-                SpatialBundle {
-                    transform: Transform::from_translation(
-                        level_shapes.player_start_point.extend(0.),
-                    ),
-                    ..Default::default()
-                },
-                ConstructPlayer,
-            ));
+            for index in 0..mode.players {
+                let start = level_shapes.player_start_point + mode.start_offset(index);
+                p.spawn((
+                    SpatialBundle {
+                        transform: Transform::from_translation(start.extend(0.)),
+                        ..Default::default()
+                    },
+                    ConstructPlayer,
+                    PlayerIndex(index),
+                ));
+            }
         });
 
+    spawn_triggers(&mut commands, &level, &level_shapes);
+
     commands.insert_resource(NextState(Some(GameState::InGame)));
 }
 
@@ -283,24 +337,18 @@ fn define_level_shape(rng: &Rng, length: f32, curviness: f32, segments: usize) -
         crossroads + Vec2::Y * 1000.,
     ];
 
-    let roads = target_path
+    let route = RoadCurve::through(&target_path.iter().map(|v| v.0).collect::<Vec<_>>());
+
+    let road_curves: Arc<[RoadCurve]> = [
+        route.clone(),
+        RoadCurve::through(&cross_road_points),
+        RoadCurve::through(&[crossroads, end_pos]),
+    ]
+    .into();
+
+    let roads = road_curves
         .iter()
-        .map_windows(|[a, b]| LevelRoadSegment {
-            start: a.0,
-            end: b.0,
-        })
-        .chain(
-            cross_road_points
-                .iter()
-                .map_windows(|[a, b]| LevelRoadSegment {
-                    start: **a,
-                    end: **b,
-                }),
-        )
-        .chain([LevelRoadSegment {
-            start: crossroads,
-            end: end_pos,
-        }])
+        .flat_map(|curve| curve.segments())
         .collect::<Arc<[_]>>();
 
     let sections: Arc<[LevelSections]> = target_path
@@ -362,28 +410,22 @@ fn define_level_shape(rng: &Rng, length: f32, curviness: f32, segments: usize) -
     LevelShape {
         crossroads,
         roads,
-        target_start_point: target_path
-            .first()
-            .cloned()
-            .map(|v| v.0)
-            .unwrap_or_default(),
-        target_path,
+        road_curves,
+        target_start_point: route.start(),
+        route,
         section: sections,
         player_start_point,
     }
 }
 
-fn spawn_road_segment(commands: &mut ChildBuilder, segment: &LevelRoadSegment, rng: &Rng) {
-    let start = segment.start;
-    let diff = segment.end - segment.start;
-    let total_distance = segment.end.distance(segment.start);
-    let road_tile_size_t = ROAD_TILE_SIZE / total_distance;
-    let mut current_t = 0.;
+fn spawn_road_curve(commands: &mut ChildBuilder, curve: &RoadCurve, rng: &Rng) {
+    let length = curve.length();
+    let mut current = 0.;
 
-    while current_t < 1. {
-        let point = start + diff * current_t;
+    while current < length {
+        let (point, _) = curve.sample(current);
         let tile_size_mod = rng.f32_normalized() * 0.2 + 1.;
-        current_t += tile_size_mod * road_tile_size_t;
+        current += tile_size_mod * ROAD_TILE_SIZE;
         commands.spawn((
             Name::new("road segment"),
             WithMesh::RoadTile,
@@ -402,8 +444,16 @@ fn fill_section(
     section: &LevelSections,
     rng: &Rng,
     segment: &Segment,
+    roads: &[LevelRoadSegment],
 ) {
-    fill_section_inner(segment.split_levels.max(1), commands, section, rng, segment);
+    fill_section_inner(
+        segment.split_levels.max(1),
+        commands,
+        section,
+        rng,
+        segment,
+        roads,
+    );
 }
 
 const MIN_SECTION_SIZE: f32 = 100.;
@@ -414,6 +464,7 @@ fn fill_section_inner(
     section: &LevelSections,
     rng: &Rng,
     segment: &Segment,
+    roads: &[LevelRoadSegment],
 ) {
     let subdivide = level > 0 && rng.bool() && section.size_min() > MIN_SECTION_SIZE;
     if subdivide {
@@ -427,7 +478,7 @@ fn fill_section_inner(
                     * (1. + segment.checkpoint_variation * rng.f32_normalized()),
                 ..segment.clone()
             };
-            fill_section_inner(level, commands, section, rng, &segment);
+            fill_section_inner(level, commands, section, rng, &segment, roads);
         }
     } else {
         let center = section.section_center();
@@ -444,6 +495,13 @@ fn fill_section_inner(
                 place_trees(2, p, section, rng, tree_density);
             });
 
+        let placement = DangerPlacement {
+            roads,
+            road_chance: segment.road_patrol_chance,
+            loop_chance: segment.loop_patrol_chance,
+            squad_size: segment.seraphim_squad_size,
+        };
+
         for (danger, root_danger_density) in &segment.danger_densities {
             let name = format!("{danger:?}");
             commands
@@ -453,7 +511,7 @@ fn fill_section_inner(
                     let danger_density = (simplex_noise_2d_seeded(center, seed).abs() * 0.5 + 0.5)
                         * root_danger_density;
                     let danger_density = danger_density.clamp(0., 1.);
-                    place_danger(2, p, section, rng, danger_density, danger);
+                    place_danger(2, p, section, rng, danger_density, danger, &placement);
                 });
         }
 
@@ -559,6 +617,7 @@ fn place_danger(
     rng: &Rng,
     density: f32,
     danger: &DangerType,
+    placement: &DangerPlacement,
 ) {
     let main_axis = section.main_axis_min_length();
     let cross_axis = section.cross_axis_min_length();
@@ -572,6 +631,7 @@ fn place_danger(
                 rng,
                 density,
                 danger,
+                placement,
             );
         }
         error!("No danger distances available");
@@ -579,7 +639,15 @@ fn place_danger(
     };
 
     if (main_axis < *size || cross_axis < *size) && level > 0 {
-        place_danger(level - 1, commands, section, rng, density, danger);
+        place_danger(
+            level - 1,
+            commands,
+            section,
+            rng,
+            density,
+            danger,
+            placement,
+        );
         return;
     }
 
@@ -603,7 +671,7 @@ fn place_danger(
             let spawn_here = rng.f32() < density;
             if !spawn_here {
                 if level > 0 {
-                    place_danger(level - 1, commands, &inner, rng, density, danger);
+                    place_danger(level - 1, commands, &inner, rng, density, danger, placement);
                 }
                 continue;
             }
@@ -611,14 +679,118 @@ fn place_danger(
                 rng.f32_normalized() * 0.5 + 0.5,
                 rng.f32_normalized() * 0.5 + 0.5,
             ));
-            commands.spawn((
+            placement.spawn(commands, point, &inner, rng, danger);
+        }
+    }
+}
+
+const PATROL_ROAD_LENGTH: f32 = 300.;
+const PATROL_ROAD_OFFSET: f32 = 60.;
+const PATROL_LOOP_INSET: f32 = 0.25;
+
+struct DangerPlacement<'a> {
+    roads: &'a [LevelRoadSegment],
+    road_chance: f32,
+    loop_chance: f32,
+    squad_size: usize,
+}
+
+impl<'a> DangerPlacement<'a> {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        point: Vec2,
+        section: &LevelSections,
+        rng: &Rng,
+        danger: &DangerType,
+    ) {
+        let route = self.route(point, section, rng);
+
+        if !matches!(danger, DangerType::StealthySeraphim) || self.squad_size <= 1 {
+            let mut danger = commands.spawn((
                 SpatialBundle {
                     transform: Transform::from_translation(point.extend(0.)),
                     ..Default::default()
                 },
                 *danger,
             ));
+            if let Some(route) = route {
+                danger.insert(route);
+            }
+            return;
         }
+
+        let squad = commands
+            .spawn((
+                SpatialBundle::default(),
+                Name::new("Seraphim Squad"),
+                SquadBlackboard::default(),
+            ))
+            .id();
+
+        for offset in squad_offsets(self.squad_size) {
+            let mut member = commands.spawn((
+                SpatialBundle {
+                    transform: Transform::from_translation((point + offset).extend(0.)),
+                    ..Default::default()
+                },
+                *danger,
+                SquadMember(squad),
+            ));
+            if let Some(route) = &route {
+                member.insert(route.clone());
+            }
+        }
+    }
+
+    fn route(&self, point: Vec2, section: &LevelSections, rng: &Rng) -> Option<PatrolRoute> {
+        let roll = rng.f32();
+        if roll < self.road_chance {
+            self.road_route(point)
+        } else if roll < self.road_chance + self.loop_chance {
+            Some(Self::loop_route(section))
+        } else {
+            None
+        }
+    }
+
+    fn road_route(&self, point: Vec2) -> Option<PatrolRoute> {
+        let (closest, direction) = self
+            .roads
+            .iter()
+            .map(|road| {
+                let direction = (road.end - road.start).normalize_or_zero();
+                let length = road.end.distance(road.start);
+                let along = (point - road.start).dot(direction).clamp(0., length);
+                (road.start + direction * along, direction)
+            })
+            .min_by(|(a, _), (b, _)| a.distance(point).total_cmp(&b.distance(point)))?;
+
+        let normal = direction.perp();
+        let side = (point - closest).dot(normal).signum() * PATROL_ROAD_OFFSET;
+        let closest = closest + normal * side;
+
+        Some(PatrolRoute::new(
+            [
+                closest - direction * PATROL_ROAD_LENGTH,
+                closest + direction * PATROL_ROAD_LENGTH,
+            ],
+            false,
+        ))
+    }
+
+    fn loop_route(section: &LevelSections) -> PatrolRoute {
+        let center = section.section_center();
+        PatrolRoute::new(
+            [
+                section.bottom_left,
+                section.bottom_right,
+                section.top_right,
+                section.top_left,
+            ]
+            .map(|corner| corner.lerp(center, PATROL_LOOP_INSET)),
+            true,
+        )
     }
 }
 
@@ -767,7 +939,8 @@ fn randomized_midpoint(a: &Vec2, b: &Vec2, rng: &Rng) -> Vec2 {
 pub struct LevelShape {
     pub crossroads: Vec2,
     pub roads: Arc<[LevelRoadSegment]>,
-    pub target_path: Arc<[(Vec2, f32)]>,
+    pub road_curves: Arc<[RoadCurve]>,
+    pub route: RoadCurve,
     pub section: Arc<[LevelSections]>,
     pub player_start_point: Vec2,
     pub target_start_point: Vec2,
@@ -5,12 +5,15 @@ use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 use crate::{assets::WithMesh, in_game::danger::DangerSpawner};
 
 use super::{
+    banish::BanishImmune,
     danger::{Chase, Chasing, Danger, DangerType, Resting},
+    game_clock::GameClock,
     movement::CanMove,
     player::Player,
     ritual::{Person, RitualProceeding},
     schedule::{InGamePostUpdate, InGamePreUpdate},
     souls::LethalTouch,
+    teleport::PathInteraction,
     InGame,
 };
 
@@ -27,7 +30,7 @@ fn spawn_guardian_angel(
     dangers: Query<Entity, With<GuardianAngel>>,
     player: Query<&Transform, With<Player>>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     if !dangers.is_empty() {
         return;
@@ -61,6 +64,7 @@ fn spawn_guardian_angel(
             GuardianAngel,
             Name::new("Guardian Angel"),
             Danger(20.),
+            PathInteraction::Kill,
             CanMove { move_speed: 150. },
             Thinker::build()
                 .label("Guardian Angel Thinker")
@@ -84,6 +88,7 @@ fn spawn_guardian_angel(
                 WithMesh::GuardianAngel
             },
             LethalTouch,
+            BanishImmune,
             InGame,
             DangerType::GuardianAngel,
             DangerSpawner(person),
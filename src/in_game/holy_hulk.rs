@@ -4,9 +4,14 @@ use big_brain::{prelude::FirstToScore, thinker::Thinker};
 use crate::assets::WithMesh;
 
 use super::{
-    danger::{Chase, Chasing, Danger, Meandering, Resting, Restless, Restlessness, SpawnTime},
+    danger::{
+        Chase, Chasing, Danger, Meandering, Patrol, Patrolling, Resting, Restless, Restlessness,
+        SpawnTime,
+    },
+    game_clock::GameClock,
     movement::CanMove,
     souls::LethalTouch,
+    teleport::PathInteraction,
 };
 
 #[derive(Component)]
@@ -15,13 +20,14 @@ pub struct HolyHulk;
 pub fn spawn_holy_hulk(
     dangers: Query<Entity, (With<HolyHulk>, Without<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
         commands.entity(danger).insert((
             Name::new("Holy Hulk"),
             Danger(20.),
+            PathInteraction::Block,
             CanMove { move_speed: 50. },
             SpawnTime(now),
             Restlessness {
@@ -43,6 +49,7 @@ pub fn spawn_holy_hulk(
                         target_distance: 0.,
                     },
                 )
+                .when(Patrol, Patrolling)
                 .when(
                     Restless,
                     Meandering {
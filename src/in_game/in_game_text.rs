@@ -6,13 +6,20 @@ use bevy::prelude::*;
 use bevy_ui_dsl::*;
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 
-use super::{schedule::InGameUpdate, CurrentLevel, InGame};
+use super::{game_clock::GameClock, schedule::InGameUpdate, CurrentLevel, InGame};
 
 pub fn in_game_text_plugin(app: &mut ReloadableAppContents) {
     app.add_systems(InGameUpdate, display_new_text)
         .add_systems(OnEnter(AppState::InGame), setup);
 }
 
+#[derive(Resource, Default, Debug)]
+pub struct ActiveDialogue {
+    pub text: String,
+    pub remaining: f32,
+    pub serial: usize,
+}
+
 #[derive(Component, Clone)]
 pub struct InGameText {
     current_index: Option<usize>,
@@ -47,10 +54,12 @@ pub fn display_new_text(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut existing_text: Query<(Entity, &mut InGameText)>,
-    time: Res<Time>,
+    time: Res<GameClock>,
     level: Res<CurrentLevel>,
+    mut dialogue: ResMut<ActiveDialogue>,
 ) {
     let delta = time.delta_seconds();
+    dialogue.remaining -= delta;
     for (entity, mut in_game_text) in &mut existing_text {
         in_game_text.time_so_far += delta;
         let now = in_game_text.time_so_far;
@@ -62,6 +71,9 @@ pub fn display_new_text(
             }
             current_text = Some((id, text.as_str()));
         }
+        if dialogue.remaining > 0. {
+            current_text = Some((level.timed_text.len() + dialogue.serial, &dialogue.text));
+        }
 
         if let Some((id, text)) = current_text {
             if !in_game_text.visible {
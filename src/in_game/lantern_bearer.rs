@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use big_brain::{prelude::FirstToScore, thinker::Thinker};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use crate::assets::WithMesh;
+
+use super::{
+    danger::{
+        Chase, Chasing, Danger, Meandering, Patrol, Patrolling, Resting, Restless, Restlessness,
+        SpawnTime,
+    },
+    game_clock::GameClock,
+    movement::CanMove,
+    schedule::InGameUpdate,
+    souls::LethalTouch,
+    teleport::PathInteraction,
+};
+
+#[derive(Component)]
+pub struct LanternBearer;
+
+#[derive(Component)]
+pub struct Lantern {
+    pub radius: f32,
+}
+
+pub fn lantern_bearer_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(InGameUpdate, spawn_lantern_bearer)
+        .add_systems(PostUpdate, draw_lantern);
+}
+
+fn spawn_lantern_bearer(
+    dangers: Query<Entity, (With<LanternBearer>, Without<Danger>)>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for danger in &dangers {
+        commands.entity(danger).insert((
+            Name::new("Lantern Bearer"),
+            Danger(15.),
+            PathInteraction::Block,
+            Lantern { radius: 120. },
+            CanMove { move_speed: 35. },
+            SpawnTime(now),
+            Restlessness {
+                per_second: 15.,
+                current_restlessness: 0.,
+            },
+            Thinker::build()
+                .label("Lantern Bearer Thinker")
+                .picker(FirstToScore { threshold: 0.8 })
+                .when(
+                    Chase {
+                        trigger_distance: 350.,
+                        max_distance: 450.,
+                        target_distance: 60.,
+                    },
+                    Chasing {
+                        max_distance: 450.,
+                        player: None,
+                        target_distance: 60.,
+                    },
+                )
+                .when(Patrol, Patrolling)
+                .when(
+                    Restless,
+                    Meandering {
+                        recovery_per_second: 30.,
+                    },
+                )
+                .otherwise(Resting),
+            WithMesh::GuardianAngel,
+            LethalTouch,
+        ));
+    }
+}
+
+fn draw_lantern(lanterns: Query<(&GlobalTransform, &Lantern)>, mut painter: ShapePainter) {
+    painter.color = Color::rgba(1., 0.85, 0.4, 0.25);
+    painter.hollow = false;
+    for (transform, lantern) in &lanterns {
+        let mut position = transform.translation();
+        position.z = -3.;
+        painter.set_translation(position);
+        painter.circle(lantern.radius);
+    }
+}
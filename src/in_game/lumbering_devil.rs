@@ -4,9 +4,15 @@ use big_brain::{prelude::FirstToScore, thinker::Thinker};
 use crate::assets::WithMesh;
 
 use super::{
-    danger::{Chase, Chasing, Danger, Meandering, Resting, Restless, Restlessness, SpawnTime},
+    beat::StepsOnBeat,
+    danger::{
+        Chase, Chasing, Danger, Meandering, Patrol, Patrolling, Resting, Restless, Restlessness,
+        SpawnTime,
+    },
+    game_clock::GameClock,
     movement::CanMove,
     souls::LethalTouch,
+    teleport::PathInteraction,
 };
 
 #[derive(Component)]
@@ -15,14 +21,19 @@ pub struct LumberingDevil;
 pub fn spawn_lumbering_devil(
     dangers: Query<Entity, (With<LumberingDevil>, Without<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
         commands.entity(danger).insert((
             Name::new("Lumbering Devil"),
             Danger(20.),
+            PathInteraction::Damage(10.),
             CanMove { move_speed: 40. },
+            StepsOnBeat {
+                base_speed: 40.,
+                stride: 0.,
+            },
             SpawnTime(now),
             Restlessness {
                 per_second: 20.,
@@ -43,6 +54,7 @@ pub fn spawn_lumbering_devil(
                         target_distance: 0.,
                     },
                 )
+                .when(Patrol, Patrolling)
                 .when(
                     Restless,
                     Meandering {
@@ -1,8 +1,15 @@
 mod actions;
+mod aim_assist;
 mod angelic_archers;
+mod banish;
+mod beat;
+mod boss;
 mod checkpoints;
+mod co_op;
 mod danger;
 mod divine_detonator;
+mod escort;
+mod game_clock;
 mod game_completed;
 mod game_over;
 mod game_state;
@@ -10,16 +17,27 @@ mod generate_level;
 mod guardian_angel;
 mod holy_hulk;
 mod in_game_text;
+mod lantern_bearer;
 mod lumbering_devil;
 mod movement;
+mod objectives;
 mod pause_screen;
 mod player;
+mod progression;
 mod ritual;
+mod ritual_minigame;
+mod road_curve;
 mod schedule;
 mod shadow;
+mod slow_motion;
+mod snapshots;
 mod souls;
+mod squads;
 mod stealthy_seraphim;
 mod teleport;
+mod timeline;
+mod triggers;
+mod versus;
 
 use bevy::{input::common_conditions::input_toggle_active, prelude::*};
 use bevy_common_assets::yaml::YamlAssetPlugin;
@@ -42,26 +60,45 @@ use crate::{
 };
 
 use self::{
-    actions::PlayerAction,
-    danger::DangerType,
+    actions::{detect_input_device, InputDevice, PlayerAction},
+    aim_assist::aim_assist_plugin,
+    banish::Banish,
+    beat::{beat_plugin, Beat, BeatClock},
+    boss::{BossEncounter, BossHit},
+    co_op::co_op_plugin,
+    escort::escort_plugin,
+    game_clock::{game_clock_plugin, GameClock},
     game_completed::GameCompletedPlugin,
     game_over::GameOverPlugin,
     game_state::{GameState, PauseState},
     generate_level::*,
+    in_game_text::ActiveDialogue,
     movement::*,
+    objectives::{objectives_plugin, ObjectiveTracker},
     pause_screen::PausePlugin,
     player::*,
+    ritual_minigame::{ritual_minigame_plugin, RitualPerformance},
     schedule::*,
     shadow::*,
+    slow_motion::{slow_motion_plugin, SlowMotion, SlowMotionPulse},
+    snapshots::{snapshot_plugin, RestoreSnapshot, WorldSnapshots},
     souls::{Damage, Death},
     teleport::*,
+    timeline::{timeline_plugin, RunLevelAction, Timeline},
+    triggers::trigger_plugin,
+    versus::{versus_plugin, RaceResults},
 };
 use dexterous_developer::{
     dexterous_developer_setup, ReloadableAppContents, ReloadableElementsSetup,
 };
 
-pub use generate_level::{CurrentLevel, Levels};
+pub use actions::{input_label, Controls, CONTROLS_FILE};
+pub use beat::TempoSettings;
+pub use co_op::GameMode;
+pub use danger::DangerType;
+pub use generate_level::{CurrentLevel, Levels, Segment};
 pub use player::TrackingCamera;
+pub use progression::{Progression, Upgrade, PROGRESSION_FILE};
 pub struct InGamePlugin;
 
 impl Plugin for InGamePlugin {
@@ -87,6 +124,25 @@ impl Plugin for InGamePlugin {
         .add_plugins(YamlAssetPlugin::<Levels>::new(&["lvl.yaml"]))
         .add_event::<Damage>()
         .add_event::<Death>()
+        .add_event::<BossHit>()
+        .add_event::<Banish>()
+        .add_event::<RestoreSnapshot>()
+        .add_event::<Beat>()
+        .add_event::<RunLevelAction>()
+        .add_event::<SlowMotionPulse>()
+        .init_resource::<BossEncounter>()
+        .init_resource::<InputDevice>()
+        .init_resource::<GameMode>()
+        .init_resource::<RaceResults>()
+        .init_resource::<WorldSnapshots>()
+        .init_resource::<BeatClock>()
+        .init_resource::<GameClock>()
+        .init_resource::<SlowMotion>()
+        .init_resource::<ObjectiveTracker>()
+        .init_resource::<RitualPerformance>()
+        .init_resource::<Timeline>()
+        .init_resource::<ActiveDialogue>()
+        .add_systems(Update, detect_input_device)
         .add_plugins(
             StateInspectorPlugin::<GameState>::default()
                 .run_if(input_toggle_active(false, KeyCode::F1)),
@@ -125,22 +181,42 @@ impl Plugin for InGamePlugin {
 
 #[dexterous_developer_setup(in_game)]
 fn reloadable(app: &mut ReloadableAppContents) {
+    game_clock_plugin(app);
     player_plugin(app);
+    aim_assist_plugin(app);
+    co_op_plugin(app);
+    versus_plugin(app);
     shadow_plugin(app);
     movement_plugin(app);
     souls_plugin(app);
     teleport_plugin(app);
+    slow_motion_plugin(app);
     checkpoint_plugin(app);
+    snapshot_plugin(app);
     danger_plugin(app);
     level_generate_plugin(app);
     ritual_plugin(app);
+    ritual_minigame_plugin(app);
+    objectives_plugin(app);
+    escort_plugin(app);
+    beat_plugin(app);
     in_game_text_plugin(app);
+    timeline_plugin(app);
+    trigger_plugin(app);
 }
 
 #[derive(Component)]
 struct InGame;
 
-fn exit(mut commands: Commands, _query: Query<Entity, With<InGame>>, _windows: Query<&mut Window>) {
+fn exit(
+    mut commands: Commands,
+    _query: Query<Entity, With<InGame>>,
+    _windows: Query<&mut Window>,
+    mut camera: Query<(&mut OrthographicProjection, &TrackingCamera)>,
+) {
+    for (mut projection, tracking) in &mut camera {
+        projection.scale = tracking.min_scale;
+    }
     commands.insert_resource(NextState(Some(GameState::None)));
     commands.insert_resource(NextState(Some(PauseState::None)));
 }
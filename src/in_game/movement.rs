@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 
-use super::schedule::InGameUpdate;
+use super::{banish::Stunned, game_clock::GameClock, schedule::InGameUpdate};
 
 pub fn movement_plugin(app: &mut ReloadableAppContents) {
     app.add_systems(InGameUpdate, movement);
@@ -22,10 +22,22 @@ impl Default for CanMove {
 #[component(storage = "SparseSet")]
 pub struct Moving(pub Vec2);
 
-pub fn movement(mut mover: Query<(&mut Transform, &Moving, &CanMove)>, time: Res<Time>) {
+#[derive(Component, Clone, Default)]
+#[component(storage = "SparseSet")]
+pub struct Flocking(pub Vec2);
+
+pub fn movement(
+    mut mover: Query<
+        (&mut Transform, Option<&Moving>, Option<&Flocking>, &CanMove),
+        Without<Stunned>,
+    >,
+    time: Res<GameClock>,
+) {
     let delta = time.delta_seconds();
-    for (mut transform, movement, can_move) in mover.iter_mut() {
-        let direction = Vec3::new(movement.0.x, movement.0.y, 0.);
+    for (mut transform, movement, flocking, can_move) in mover.iter_mut() {
+        let movement =
+            movement.map(|v| v.0).unwrap_or_default() + flocking.map(|v| v.0).unwrap_or_default();
+        let direction = Vec3::new(movement.x, movement.y, 0.);
         if direction.length_squared() < 0.1 {
             continue;
         }
@@ -0,0 +1,350 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_ui_dsl::*;
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    assets::WithMesh,
+    ui::{classes::*, intermediary_node_bundles::IntoIntermediaryNodeBundle},
+};
+
+use super::{
+    boss::BossEncounter,
+    checkpoints::{CheckpointCollected, Checkpoints},
+    co_op::GameMode,
+    game_state::GameState,
+    player::{DiedOf, Player},
+    ritual::{Person, Ritual},
+    schedule::{InGamePostUpdate, InGameUpdate},
+    souls::DamageType,
+    CurrentLevel, InGame,
+};
+
+pub fn objectives_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_objectives)
+        .add_systems(
+            InGameUpdate,
+            (spawn_acquaintances, setup_objectives_ui, track_objectives),
+        )
+        .add_systems(
+            InGamePostUpdate,
+            check_objectives.run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(PostUpdate, draw_objectives_ui);
+}
+
+const MEETING_DISTANCE: f32 = 30.;
+const ACQUAINTANCE_OFFSET: f32 = 60.;
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Objective {
+    ReachRitual,
+    ReachCrossroads { radius: f32 },
+    CollectCheckpoints { count: usize },
+    Survive,
+    NoCheckpointsUsed,
+    MeetPeople { count: usize },
+}
+
+impl Objective {
+    fn target(&self) -> usize {
+        match self {
+            Objective::CollectCheckpoints { count } | Objective::MeetPeople { count } => *count,
+            _ => 1,
+        }
+    }
+
+    fn label(&self, progress: usize) -> String {
+        match self {
+            Objective::ReachRitual => "Reach the summoning".to_string(),
+            Objective::ReachCrossroads { .. } => "Reach the crossroads".to_string(),
+            Objective::CollectCheckpoints { count } => {
+                format!("Collect checkpoints {progress}/{count}")
+            }
+            Objective::Survive => "Survive until the song ends".to_string(),
+            Objective::NoCheckpointsUsed => "Don't use any checkpoints".to_string(),
+            Objective::MeetPeople { count } => format!("Meet people {progress}/{count}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum ObjectiveMode {
+    #[default]
+    All,
+    Any,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectiveState {
+    Pending,
+    Complete,
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectiveStatus {
+    pub objective: Objective,
+    pub progress: usize,
+    pub state: ObjectiveState,
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ObjectiveTracker {
+    pub mode: ObjectiveMode,
+    pub objectives: Vec<ObjectiveStatus>,
+}
+
+impl ObjectiveTracker {
+    pub fn is_empty(&self) -> bool {
+        self.objectives.is_empty()
+    }
+
+    pub fn satisfied(&self) -> bool {
+        let mut states = self.objectives.iter().map(|v| v.state);
+        match self.mode {
+            ObjectiveMode::All => states.all(|v| v == ObjectiveState::Complete),
+            ObjectiveMode::Any => states.any(|v| v == ObjectiveState::Complete),
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        let mut states = self.objectives.iter().map(|v| v.state);
+        match self.mode {
+            ObjectiveMode::All => states.any(|v| v == ObjectiveState::Failed),
+            ObjectiveMode::Any => states.all(|v| v == ObjectiveState::Failed),
+        }
+    }
+
+    pub fn resolve(&mut self, reached_ritual: bool, survived: bool) {
+        for status in self.objectives.iter_mut() {
+            if status.state != ObjectiveState::Pending {
+                continue;
+            }
+            let complete = match status.objective {
+                Objective::ReachRitual => reached_ritual,
+                Objective::Survive => survived,
+                Objective::NoCheckpointsUsed => true,
+                _ => false,
+            };
+            status.state = if complete {
+                ObjectiveState::Complete
+            } else {
+                ObjectiveState::Failed
+            };
+        }
+    }
+
+    pub fn requires_ritual(&self) -> bool {
+        self.objectives
+            .iter()
+            .any(|v| matches!(v.objective, Objective::ReachRitual))
+    }
+}
+
+#[derive(Component)]
+struct Acquaintance {
+    met: bool,
+}
+
+#[derive(Component)]
+struct ObjectiveText(usize);
+
+fn reset_objectives(mut commands: Commands, level: Res<CurrentLevel>) {
+    commands.insert_resource(ObjectiveTracker {
+        mode: level.objective_mode,
+        objectives: level
+            .objectives
+            .iter()
+            .map(|objective| ObjectiveStatus {
+                objective: objective.clone(),
+                progress: 0,
+                state: ObjectiveState::Pending,
+            })
+            .collect(),
+    });
+}
+
+fn spawn_acquaintances(
+    people: Query<&Person, Added<Person>>,
+    tracker: Res<ObjectiveTracker>,
+    mut commands: Commands,
+) {
+    let count = tracker
+        .objectives
+        .iter()
+        .filter_map(|v| match v.objective {
+            Objective::MeetPeople { count } => Some(count),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default();
+    if count == 0 {
+        return;
+    }
+    for person in &people {
+        let route = &person.1;
+        for i in 0..count {
+            let distance = route.length() * (i + 1) as f32 / (count + 1) as f32;
+            let (point, direction) = route.sample(distance);
+            let side = if i % 2 == 0 { 1. } else { -1. };
+            let position = point + direction.perp() * ACQUAINTANCE_OFFSET * side;
+            commands.spawn((
+                Name::new("Acquaintance"),
+                SpatialBundle {
+                    transform: Transform::from_translation(position.extend(0.)),
+                    ..Default::default()
+                },
+                WithMesh::Person,
+                Acquaintance { met: false },
+                InGame,
+            ));
+        }
+    }
+}
+
+fn setup_objectives_ui(
+    tracker: Res<ObjectiveTracker>,
+    texts: Query<&ObjectiveText>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    if tracker.is_empty() || !texts.is_empty() {
+        return;
+    }
+    let mut objective_texts = vec![];
+    let r = root(objectives_root, &asset_server, &mut commands, |p| {
+        if tracker.objectives.len() > 1 {
+            text(
+                match tracker.mode {
+                    ObjectiveMode::All => "Complete all of:",
+                    ObjectiveMode::Any => "Complete any of:",
+                },
+                (),
+                standard_text,
+                p,
+            );
+        }
+        for (i, _) in tracker.objectives.iter().enumerate() {
+            objective_texts.push((text("", (), standard_text, p), i));
+        }
+    });
+    for (entity, i) in objective_texts {
+        commands.entity(entity).insert(ObjectiveText(i));
+    }
+    commands.entity(r).insert(InGame);
+}
+
+fn track_objectives(
+    mut tracker: ResMut<ObjectiveTracker>,
+    players: Query<(&GlobalTransform, &CheckpointCollected, &Checkpoints), With<Player>>,
+    mut acquaintances: Query<(&GlobalTransform, &mut Acquaintance)>,
+    rituals: Query<&Ritual>,
+) {
+    if tracker.is_empty() {
+        return;
+    }
+    let positions = players
+        .iter()
+        .map(|(transform, ..)| transform.translation().xy())
+        .collect::<Vec<_>>();
+
+    for (transform, mut acquaintance) in &mut acquaintances {
+        let position = transform.translation().xy();
+        if positions
+            .iter()
+            .any(|player| player.distance(position) < MEETING_DISTANCE)
+        {
+            acquaintance.met = true;
+        }
+    }
+
+    let collected = players
+        .iter()
+        .map(|(_, collected, _)| collected.0)
+        .sum::<usize>();
+    let used = players
+        .iter()
+        .map(|(_, _, checkpoints)| checkpoints.used)
+        .sum::<usize>();
+    let met = acquaintances.iter().filter(|(_, v)| v.met).count();
+    let ritual = rituals.get_single().ok().map(|v| v.position);
+
+    for status in tracker.objectives.iter_mut() {
+        if status.state != ObjectiveState::Pending {
+            continue;
+        }
+        let target = status.objective.target();
+        match status.objective {
+            Objective::ReachCrossroads { radius } => {
+                if let Some(ritual) = ritual {
+                    if positions.iter().any(|v| v.distance(ritual) < radius) {
+                        status.progress = 1;
+                    }
+                }
+            }
+            Objective::CollectCheckpoints { .. } => status.progress = collected.min(target),
+            Objective::MeetPeople { .. } => status.progress = met.min(target),
+            Objective::NoCheckpointsUsed => {
+                if used > 0 {
+                    status.state = ObjectiveState::Failed;
+                }
+                continue;
+            }
+            Objective::ReachRitual | Objective::Survive => continue,
+        }
+        if status.progress >= target {
+            status.state = ObjectiveState::Complete;
+        }
+    }
+}
+
+fn check_objectives(
+    tracker: Res<ObjectiveTracker>,
+    players: Query<Entity, With<Player>>,
+    encounter: Res<BossEncounter>,
+    level: Res<CurrentLevel>,
+    mode: Res<GameMode>,
+    mut commands: Commands,
+) {
+    if tracker.is_empty() || mode.is_versus() {
+        return;
+    }
+    if tracker.failed() {
+        for player in &players {
+            commands
+                .entity(player)
+                .insert(DiedOf(DamageType::ObjectiveFailed));
+        }
+        commands.insert_resource(NextState(Some(GameState::Failed)));
+    } else if tracker.satisfied() && encounter.satisfied(level.boss.as_ref()) {
+        commands.insert_resource(NextState(Some(GameState::Complete)));
+    }
+}
+
+fn draw_objectives_ui(
+    tracker: Res<ObjectiveTracker>,
+    mut texts: Query<(&ObjectiveText, &mut Text)>,
+) {
+    for (ObjectiveText(index), mut text) in &mut texts {
+        let Some(status) = tracker.objectives.get(*index) else {
+            continue;
+        };
+        let Some(section) = text.sections.first_mut() else {
+            continue;
+        };
+        let label = status.objective.label(status.progress);
+        section.value = match status.state {
+            ObjectiveState::Pending => label,
+            ObjectiveState::Complete => format!("{label} - done"),
+            ObjectiveState::Failed => format!("{label} - failed"),
+        };
+        section.style.color = match status.state {
+            ObjectiveState::Failed => crate::ui::colors::BAD_COLOR,
+            _ => crate::ui::colors::PRIMARY_COLOR,
+        };
+    }
+}
@@ -7,18 +7,24 @@ use crate::{
 };
 
 use super::{
-    actions::{input_manager, PlayerAction},
+    actions::{input_manager, Controls, InputDevice, PlayerAction},
+    aim_assist::AimAssist,
+    banish::{Banish, BanishCandidate},
     checkpoints::{CheckpointCollected, Checkpoints},
-    danger::Danger,
+    co_op::{Eliminated, GameMode, PlayerIndex},
+    escort::DangerTarget,
     game_state::{GameState, TemporaryIgnore},
     movement::{CanMove, Moving},
+    progression::Progression,
     schedule::{InGamePreUpdate, InGameUpdate},
     shadow::{CheckForShadow, InShadow},
+    snapshots::RestoreSnapshot,
     souls::{DamageType, Death, MaxSouls, Souls, SunSensitivity},
-    teleport::{CanTeleport, StartTeleport, TargetInRange, Teleporting},
-    InGame,
+    teleport::{CanTeleport, TargetInRange, TeleportEnergy, TeleportLocked, Teleporting},
+    versus::RaceResults,
+    CurrentLevel, InGame,
 };
-use bevy::{ecs::query::Has, prelude::*, window::PrimaryWindow};
+use bevy::{ecs::query::Has, math::Vec3Swizzles, prelude::*, window::PrimaryWindow};
 use bevy_tweening::Lerp;
 use bevy_ui_dsl::*;
 use bevy_vector_shapes::{
@@ -50,7 +56,13 @@ pub fn player_plugin(app: &mut ReloadableAppContents) {
     )
     .add_systems(
         PostUpdate,
-        (draw_target, end_game, draw_souls_ui, draw_player),
+        (
+            draw_target,
+            end_game,
+            draw_souls_ui,
+            draw_energy_ui,
+            draw_player,
+        ),
     );
 }
 
@@ -73,12 +85,17 @@ pub struct CheckpointsConsumedForTeleport(pub usize);
 pub struct DiedOf(pub DamageType);
 
 pub fn construct_player(
-    players: Query<(Entity, &GlobalTransform), With<ConstructPlayer>>,
+    players: Query<(Entity, &GlobalTransform, Option<&PlayerIndex>), With<ConstructPlayer>>,
     mut commands: Commands,
     _assets: Res<MainGameAssets>,
     _material: Res<MainColorMaterial>,
+    level: Res<CurrentLevel>,
+    controls: Res<Controls>,
+    mode: Res<GameMode>,
+    progression: Res<Progression>,
 ) {
-    for (player_id, transform) in players.iter() {
+    for (player_id, transform, index) in players.iter() {
+        let index = index.map(|v| v.0).unwrap_or_default();
         let position = transform.translation();
         let target_id = commands
             .spawn((
@@ -103,26 +120,37 @@ pub fn construct_player(
                     ..Default::default()
                 },
                 Player,
-                CanTeleport::default(),
-                CanMove::default(),
+                DangerTarget,
+                progression.can_teleport(),
+                progression.can_move(),
                 Moving::default(),
-                input_manager(),
+                input_manager(&controls, index, mode.players),
+                AimAssist::default(),
                 (
                     CheckForShadow,
                     Souls(50.),
                     MaxSouls(50.),
-                    SunSensitivity(5.),
+                    progression.sun_sensitivity(),
                 ),
                 (
-                    Checkpoints {
-                        checkpoints: Default::default(),
-                        max_checkpoints: 3,
-                    },
+                    Checkpoints::new(progression.max_checkpoints()),
                     CheckpointsConsumedForTeleport(0),
                     CheckpointCollected(0),
                 ),
                 WithMesh::Player,
             ));
+
+        if mode.is_co_op() {
+            commands
+                .entity(player_id)
+                .insert((PlayerIndex(index), InputDevice::for_player(index)));
+        }
+
+        if let Some(settings) = &level.teleport_energy {
+            commands
+                .entity(player_id)
+                .insert(TeleportEnergy::new(settings.clone()));
+        }
     }
 }
 
@@ -172,11 +200,13 @@ pub fn move_player(mut player: Query<(&mut Moving, &ActionState<PlayerAction>)>)
 }
 
 pub fn trigger_teleport(
-    player: Query<(Entity, &ActionState<PlayerAction>)>,
+    player: Query<(Entity, &ActionState<PlayerAction>, Has<TeleportLocked>)>,
     mut commands: Commands,
 ) {
-    for (player, actions) in &player {
-        if actions.pressed(PlayerAction::Teleport) {
+    for (player, actions, locked) in &player {
+        if locked {
+            commands.entity(player).remove::<Teleporting>();
+        } else if actions.pressed(PlayerAction::Teleport) {
             commands.entity(player).insert(Teleporting);
         } else if actions.just_released(PlayerAction::Teleport) {
             commands.entity(player).remove::<Teleporting>();
@@ -205,7 +235,7 @@ pub fn player_target_movement(
 }
 
 pub fn setup_souls_ui(
-    player: Query<Entity, With<Player>>,
+    player: Query<(Entity, Has<TeleportEnergy>, Option<&PlayerIndex>), With<Player>>,
     bars: Query<&SoulBar>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -215,16 +245,29 @@ pub fn setup_souls_ui(
     }
     println!("Setting Up Souls UI");
     let mut player_soul_bars = vec![];
+    let mut player_energy_bars = vec![];
     let r = root(soul_bar_root, &asset_server, &mut commands, |p| {
-        for player in player.iter() {
+        for (player, has_energy, index) in player.iter() {
             node(soul_bar_container, p, |p| {
-                player_soul_bars.push((node(soul_bar, p, |_| {}), player));
+                player_soul_bars.push((node(soul_bar, p, |_| {}), player, index.copied()));
             });
+            if has_energy {
+                node(energy_bar_container, p, |p| {
+                    player_energy_bars.push((node(energy_bar, p, |_| {}), player));
+                });
+            }
         }
     });
 
-    for (bar, player) in player_soul_bars {
+    for (bar, player, index) in player_soul_bars {
         commands.entity(bar).insert(SoulBar(player));
+        if let Some(index) = index {
+            commands.entity(bar).insert(BackgroundColor(index.color()));
+        }
+    }
+
+    for (bar, player) in player_energy_bars {
+        commands.entity(bar).insert(EnergyBar(player));
     }
 
     commands.entity(r).insert(InGame);
@@ -246,23 +289,75 @@ pub fn draw_souls_ui(
     }
 }
 
+#[derive(Component, Clone, Copy)]
+pub struct EnergyBar(Entity);
+
+pub fn draw_energy_ui(
+    players: Query<&TeleportEnergy, With<Player>>,
+    mut bars: Query<(&mut Style, &EnergyBar)>,
+) {
+    for (mut style, player) in bars.iter_mut() {
+        let Ok(energy) = players.get(player.0) else {
+            continue;
+        };
+        let ratio = energy.current.div(energy.settings.max_energy).mul(100.);
+        style.width = Val::Percent(ratio);
+    }
+}
+
 pub fn end_game(
-    mut players: Query<(Entity, &mut Checkpoints), With<Player>>,
+    mut players: Query<(Entity, &mut Checkpoints, Option<&PlayerIndex>), With<Player>>,
     mut event: EventReader<Death>,
+    mut restore: EventWriter<RestoreSnapshot>,
     mut commands: Commands,
+    mode: Res<GameMode>,
+    mut results: ResMut<RaceResults>,
 ) {
+    let mut remaining = players.iter().count();
+    let mut deaths: Vec<&Death> = vec![];
     for death in event.iter() {
-        let Ok((player, mut checkpoints)) = players.get_mut(death.entity) else {
+        if !deaths.iter().any(|v| v.entity == death.entity) {
+            deaths.push(death);
+        }
+    }
+    for death in deaths {
+        let Ok((player, mut checkpoints, index)) = players.get_mut(death.entity) else {
             continue;
         };
-        if let Some(revert) = checkpoints.checkpoints.pop_front() {
+        if let Some(revert) = checkpoints.take_selected() {
+            if let Some(snapshot) = revert.snapshot {
+                restore.send(RestoreSnapshot(snapshot));
+            }
             commands.entity(player).insert((
                 Transform::from_translation(revert.position),
                 revert.souls,
                 revert.max_souls,
             ));
+            continue;
+        }
+        commands.entity(player).insert(DiedOf(death.cause));
+        remaining = remaining.saturating_sub(1);
+        if mode.is_versus() {
+            if let Some(index) = index {
+                results.eliminated.push(index.0);
+            }
+        }
+        if remaining > 0 {
+            commands
+                .entity(player)
+                .remove::<(
+                    Player,
+                    DangerTarget,
+                    CheckForShadow,
+                    SunSensitivity,
+                    CanMove,
+                    CanTeleport,
+                    AimAssist,
+                )>()
+                .insert((Eliminated, Visibility::Hidden));
+        } else if mode.is_versus() && !results.arrivals.is_empty() {
+            commands.insert_resource(NextState(Some(GameState::Complete)));
         } else {
-            commands.entity(player).insert(DiedOf(death.cause));
             commands.insert_resource(NextState(Some(GameState::Failed)));
         }
     }
@@ -270,9 +365,15 @@ pub fn end_game(
 
 pub fn move_target(
     mut targets: Query<(&PlayerTarget, &mut Transform)>,
-    parents: Query<&PlayerTargetReference>,
+    parents: Query<(
+        &PlayerTargetReference,
+        &GlobalTransform,
+        &CanTeleport,
+        Option<&InputDevice>,
+    )>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera: Query<(&Camera, &GlobalTransform), With<TrackingCamera>>,
+    device: Res<InputDevice>,
     time: Res<Time>,
 ) {
     let set_position = if let Ok(Some(position)) = windows.get_single().map(|v| v.cursor_position())
@@ -288,14 +389,25 @@ pub fn move_target(
         None
     };
     for (parent, mut target) in targets.iter_mut() {
-        if let Some(position) = set_position {
-            target.translation = Vec3::new(position.x, position.y, 0.);
+        let Ok((target_direction, player, can_teleport, player_device)) = parents.get(parent.0)
+        else {
+            continue;
+        };
+
+        if *player_device.unwrap_or(&device) == InputDevice::Gamepad {
+            let deflection = target_direction.1.clamp_length_max(1.);
+            if deflection.length_squared() < 0.01 {
+                continue;
+            }
+            let position = player.translation().xy() + deflection * can_teleport.max_distance;
+            target.translation = position.extend(0.);
             continue;
         }
 
-        let Ok(target_direction) = parents.get(parent.0) else {
+        if let Some(position) = set_position {
+            target.translation = Vec3::new(position.x, position.y, 0.);
             continue;
-        };
+        }
 
         let direction = Vec3::new(target_direction.1.x, target_direction.1.y, 0.);
 
@@ -344,10 +456,11 @@ pub fn draw_target(
 
 fn track_camera(
     players: Query<&GlobalTransform, With<Player>>,
-    mut camera: Query<(&mut Transform, &TrackingCamera)>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection, &TrackingCamera)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
     time: Res<Time>,
 ) {
-    let Ok((mut transform, tracking)) = camera.get_single_mut() else {
+    let Ok((mut transform, mut projection, tracking)) = camera.get_single_mut() else {
         return;
     };
 
@@ -374,11 +487,28 @@ fn track_camera(
     );
 
     transform.translation += diff * delta * speed;
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let extent = players
+        .iter()
+        .map(|v| (v.translation() - focal_point).truncate().abs())
+        .fold(Vec2::ZERO, |a, b| a.max(b))
+        + Vec2::ONE * tracking.framing_margin;
+    let viewport = Vec2::new(window.width(), window.height()) / 2.;
+    let target_scale = (extent / viewport)
+        .max_element()
+        .clamp(tracking.min_scale, tracking.max_scale);
+    projection.scale = projection
+        .scale
+        .lerp(&target_scale, &(delta * tracking.zoom_speed).min(1.));
 }
 
 fn consume_checkpoint(
     mut players: Query<
         (
+            Entity,
             &GlobalTransform,
             &mut Souls,
             &mut MaxSouls,
@@ -386,14 +516,15 @@ fn consume_checkpoint(
             &mut CheckpointsConsumedForTeleport,
             &ActionState<PlayerAction>,
             &PlayerTargetReference,
+            Option<&BanishCandidate>,
         ),
         With<Player>,
     >,
     target: Query<&GlobalTransform, With<PlayerTarget>>,
-    devil: Query<(Entity, &GlobalTransform, &Danger)>,
-    mut commands: Commands,
+    mut banish: EventWriter<Banish>,
 ) {
     for (
+        player,
         player_pos,
         mut souls,
         mut max_souls,
@@ -401,6 +532,7 @@ fn consume_checkpoint(
         mut checkpoint_consumed,
         action,
         target_ref,
+        candidate,
     ) in &mut players
     {
         if !action.just_pressed(PlayerAction::Secondary) {
@@ -413,18 +545,18 @@ fn consume_checkpoint(
         let target = target.translation();
         let player_distance = target.distance(player_pos.translation());
 
-        let devil = devil.iter().find(|(_, devil_pos, devil_radius)| {
-            devil_pos.translation().distance(target) < devil_radius.0 * 2.
-        });
-
-        if let Some((devil, _, _)) = devil {
-            if let Some(checkpoint) = checkpoints.checkpoints.pop_front() {
-                let end_position = checkpoint.position;
+        if let Some(candidate) = candidate {
+            if let Some(checkpoint) = checkpoints.take_selected() {
                 checkpoint_consumed.0 += 1;
-                commands.entity(devil).insert(StartTeleport(end_position));
+                banish.send(Banish {
+                    player,
+                    danger: candidate.0,
+                    destination: checkpoint.position,
+                    souls: checkpoint.souls.0,
+                });
             }
         } else if player_distance < 30. {
-            if let Some(checkpoint) = checkpoints.checkpoints.pop_front() {
+            if let Some(checkpoint) = checkpoints.take_selected() {
                 souls.0 = checkpoint.souls.0;
                 max_souls.0 = checkpoint.max_souls.0;
                 checkpoint_consumed.0 += 1;
@@ -446,7 +578,7 @@ fn consome_checkpoint_for_health(
 ) {
     for (mut souls, mut max_souls, mut checkpoints, action_state) in &mut players {
         if action_state.just_pressed(PlayerAction::ConsumeCheckpointHealth) {
-            if let Some(checkpoint) = checkpoints.checkpoints.pop_front() {
+            if let Some(checkpoint) = checkpoints.take_selected() {
                 souls.0 = checkpoint.souls.0;
                 max_souls.0 = checkpoint.max_souls.0;
             }
@@ -457,36 +589,29 @@ fn consome_checkpoint_for_health(
 fn consume_checkpoint_teleport_devil(
     mut players: Query<
         (
+            Entity,
             &mut Checkpoints,
-            &PlayerTargetReference,
             &ActionState<PlayerAction>,
+            Option<&BanishCandidate>,
         ),
         With<Player>,
     >,
-    target: Query<&GlobalTransform, With<PlayerTarget>>,
-    devil: Query<(Entity, &GlobalTransform, &Danger)>,
-    mut commands: Commands,
+    mut banish: EventWriter<Banish>,
 ) {
-    for (mut checkpoints, target_ref, actions) in &mut players {
-        if checkpoints.checkpoints.is_empty()
-            || !actions.just_pressed(PlayerAction::SendDevilToCheckpoint)
-        {
+    for (player, mut checkpoints, actions, candidate) in &mut players {
+        if !actions.just_pressed(PlayerAction::SendDevilToCheckpoint) {
             continue;
         }
-        let Ok(target_pos) = target.get(target_ref.0) else {
+        let Some(candidate) = candidate else {
             continue;
         };
-        let target_pos = target_pos.translation();
-        for (devil, devil_pos, devil_radius) in &devil {
-            let devil_pos = devil_pos.translation();
-            if devil_pos.distance(target_pos) < devil_radius.0 {
-                if let Some(checkpoint) = checkpoints.checkpoints.pop_front() {
-                    let end_position = checkpoint.position;
-
-                    commands.entity(devil).insert(StartTeleport(end_position));
-                }
-                break;
-            }
+        if let Some(checkpoint) = checkpoints.take_selected() {
+            banish.send(Banish {
+                player,
+                danger: candidate.0,
+                destination: checkpoint.position,
+                souls: checkpoint.souls.0,
+            });
         }
     }
 }
@@ -496,6 +621,10 @@ pub struct TrackingCamera {
     pub speed: f32,
     pub facing_offset: f32,
     pub distance_for_max_speed: f32,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub framing_margin: f32,
+    pub zoom_speed: f32,
 }
 
 impl Default for TrackingCamera {
@@ -504,6 +633,10 @@ impl Default for TrackingCamera {
             speed: 200.,
             facing_offset: 50.,
             distance_for_max_speed: 100.,
+            min_scale: 0.5,
+            max_scale: 1.5,
+            framing_margin: 100.,
+            zoom_speed: 2.,
         }
     }
 }
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{movement::CanMove, souls::SunSensitivity, teleport::CanTeleport};
+
+pub const PROGRESSION_FILE: &str = "progression";
+
+const BASE_CHECKPOINTS: usize = 3;
+const BASE_SUN_SENSITIVITY: f32 = 5.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Upgrade {
+    CheckpointSlots,
+    SunResistance,
+    TeleportRange,
+    MoveSpeed,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 4] = [
+        Upgrade::CheckpointSlots,
+        Upgrade::SunResistance,
+        Upgrade::TeleportRange,
+        Upgrade::MoveSpeed,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Upgrade::CheckpointSlots => "Checkpoint Slots",
+            Upgrade::SunResistance => "Sun Resistance",
+            Upgrade::TeleportRange => "Teleport Range",
+            Upgrade::MoveSpeed => "Move Speed",
+        }
+    }
+
+    pub fn max_level(&self) -> usize {
+        match self {
+            Upgrade::CheckpointSlots => 3,
+            _ => 5,
+        }
+    }
+
+    pub fn cost(&self, level: usize) -> usize {
+        let base = match self {
+            Upgrade::CheckpointSlots => 60,
+            _ => 30,
+        };
+        base * (level + 1)
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progression {
+    pub score: usize,
+    pub checkpoint_slots: usize,
+    pub sun_resistance: usize,
+    pub teleport_range: usize,
+    pub move_speed: usize,
+}
+
+impl Progression {
+    pub fn level(&self, upgrade: Upgrade) -> usize {
+        match upgrade {
+            Upgrade::CheckpointSlots => self.checkpoint_slots,
+            Upgrade::SunResistance => self.sun_resistance,
+            Upgrade::TeleportRange => self.teleport_range,
+            Upgrade::MoveSpeed => self.move_speed,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: Upgrade) -> &mut usize {
+        match upgrade {
+            Upgrade::CheckpointSlots => &mut self.checkpoint_slots,
+            Upgrade::SunResistance => &mut self.sun_resistance,
+            Upgrade::TeleportRange => &mut self.teleport_range,
+            Upgrade::MoveSpeed => &mut self.move_speed,
+        }
+    }
+
+    pub fn next_cost(&self, upgrade: Upgrade) -> Option<usize> {
+        let level = self.level(upgrade);
+        (level < upgrade.max_level()).then(|| upgrade.cost(level))
+    }
+
+    pub fn buy(&mut self, upgrade: Upgrade) -> bool {
+        let Some(cost) = self.next_cost(upgrade) else {
+            return false;
+        };
+        if cost > self.score {
+            return false;
+        }
+        self.score -= cost;
+        *self.level_mut(upgrade) += 1;
+        true
+    }
+
+    pub fn max_checkpoints(&self) -> usize {
+        BASE_CHECKPOINTS + self.checkpoint_slots
+    }
+
+    pub fn sun_sensitivity(&self) -> SunSensitivity {
+        SunSensitivity(BASE_SUN_SENSITIVITY * (1. - 0.1 * self.sun_resistance as f32))
+    }
+
+    pub fn can_teleport(&self) -> CanTeleport {
+        let default = CanTeleport::default();
+        CanTeleport {
+            max_distance: default.max_distance + 40. * self.teleport_range as f32,
+            ..default
+        }
+    }
+
+    pub fn can_move(&self) -> CanMove {
+        CanMove {
+            move_speed: CanMove::default().move_speed + 15. * self.move_speed as f32,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_tweening::{lens::TransformScaleLens, Animator, Delay, EaseFunction, Tween};
@@ -7,10 +7,18 @@ use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 use crate::assets::WithMesh;
 
 use super::{
+    boss::BossEncounter,
+    co_op::{GameMode, PlayerIndex},
+    game_clock::GameClock,
     game_state::GameState,
+    objectives::ObjectiveTracker,
     player::{DiedOf, Player},
+    ritual_minigame::RitualPerformance,
+    road_curve::RoadCurve,
     schedule::{InGamePostUpdate, InGamePreUpdate, InGameUpdate},
-    InGame,
+    souls::DamageType,
+    versus::RaceResults,
+    CurrentLevel, InGame,
 };
 
 pub fn ritual_plugin(app: &mut ReloadableAppContents) {
@@ -22,23 +30,30 @@ pub fn ritual_plugin(app: &mut ReloadableAppContents) {
         );
 }
 
+pub const PENTAGRAM_STEPS: usize = 5;
+pub const TRIANGLE_START: f32 = 2.;
+
+pub fn pentagram_angle(step: usize) -> f32 {
+    (-360. / PENTAGRAM_STEPS as f32).to_radians() * step as f32
+}
+
 #[derive(Component)]
 pub struct Person(
     pub f32,
-    pub Arc<[(Vec2, f32)]>,
+    pub RoadCurve,
     pub Option<Handle<Mesh>>,
     pub Option<Handle<Mesh>>,
 );
 
 #[derive(Component)]
-struct TimeSoFar(f32, usize);
+pub struct TimeSoFar(pub f32);
 
 #[derive(Component)]
-struct Ritual {
-    position: Vec2,
-    radius: f32,
-    start_time: f32,
-    end_time: f32,
+pub struct Ritual {
+    pub position: Vec2,
+    pub radius: f32,
+    pub start_time: f32,
+    pub end_time: f32,
 }
 
 #[derive(Component)]
@@ -54,18 +69,11 @@ fn spawn_person(
         let radius = 30.;
 
         p.0 -= 6.;
-        let mut path = Vec::from_iter(p.1.iter().cloned());
-        let position = if let Some(last) = path.last_mut() {
-            let position = last.0;
-            last.0 -= Vec2::X * 45.;
-            position
-        } else {
-            Vec2::default()
-        };
-        p.1 = path.into_iter().collect();
+        let position = p.1.end();
+        p.1 = p.1.shortened(45.);
 
         commands.entity(person).insert((
-            TimeSoFar(0., 0),
+            TimeSoFar(0.),
             if let Some(handle) = &p.2 {
                 WithMesh::Handle(handle.clone())
             } else {
@@ -112,13 +120,10 @@ fn start_ritual(
                 Animator::new(punch_animation),
             ));
 
-            let angle_offset = (-360. / 5f32).to_radians();
-            let triangle_start = 2.;
-
-            for i in 0..5 {
-                let i = i as f32;
+            for step in 0..PENTAGRAM_STEPS {
+                let i = step as f32;
                 let delay =
-                    Delay::new(Duration::from_secs_f32(triangle_start + i) - punch_duration);
+                    Delay::new(Duration::from_secs_f32(TRIANGLE_START + i) - punch_duration);
                 let punch_animation = Tween::new(
                     EaseFunction::QuadraticIn,
                     punch_duration,
@@ -136,7 +141,7 @@ fn start_ritual(
                             .with_scale(Vec3::ZERO),
                         ..Default::default()
                     },
-                    WithMesh::PentagramTriangle(angle_offset * i),
+                    WithMesh::PentagramTriangle(pentagram_angle(step)),
                     InGame,
                     Animator::new(sequence),
                 ));
@@ -148,17 +153,77 @@ fn start_ritual(
 fn end_ritual(
     mut commands: Commands,
     rituals: Query<(&Ritual, &TimeSoFar), With<RitualProceeding>>,
-    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    players: Query<(Entity, &GlobalTransform, Option<&PlayerIndex>), With<Player>>,
+    encounter: Res<BossEncounter>,
+    level: Res<CurrentLevel>,
+    mode: Res<GameMode>,
+    mut results: ResMut<RaceResults>,
+    mut objectives: ResMut<ObjectiveTracker>,
+    performance: Res<RitualPerformance>,
 ) {
     for (ritual, time) in &rituals {
         if time.0 >= ritual.end_time {
+            if !encounter.satisfied(level.boss.as_ref()) {
+                for (player, _, _) in &players {
+                    commands.entity(player).insert(DiedOf(DamageType::Boss));
+                }
+                commands.insert_resource(NextState(Some(GameState::Failed)));
+                return;
+            }
+            if mode.is_versus() {
+                for (player, _, index) in &players {
+                    commands.entity(player).insert(DiedOf(DamageType::TimeOut));
+                    results.eliminated.extend(index.map(|index| index.0));
+                }
+                commands.insert_resource(NextState(Some(if results.arrivals.is_empty() {
+                    GameState::Failed
+                } else {
+                    GameState::Complete
+                })));
+                return;
+            }
             let position = ritual.position.extend(0.);
             let radius = ritual.radius;
-            for (player, transform) in &players {
-                if transform.translation().distance(position) <= radius {
+            let late = players
+                .iter()
+                .filter(|(_, transform, _)| transform.translation().distance(position) > radius)
+                .map(|(player, _, _)| player)
+                .collect::<Vec<_>>();
+            let remaining = players.iter().count();
+            let arrived = remaining - late.len();
+            let reached = mode.ritual_rule.satisfied(arrived, remaining);
+            let ritual_required = objectives.is_empty() || objectives.requires_ritual();
+            if reached && ritual_required && !performance.passed() {
+                for (player, _, _) in &players {
+                    commands
+                        .entity(player)
+                        .insert(DiedOf(DamageType::RitualFailed));
+                }
+                commands.insert_resource(NextState(Some(GameState::Failed)));
+                return;
+            }
+            if !objectives.is_empty() {
+                objectives.resolve(reached, !players.is_empty());
+                if objectives.satisfied() {
                     commands.insert_resource(NextState(Some(GameState::Complete)));
                     return;
                 }
+                let cause = if objectives.requires_ritual() && !reached {
+                    DamageType::TimeOut
+                } else {
+                    DamageType::ObjectiveFailed
+                };
+                for (player, _, _) in &players {
+                    commands.entity(player).insert(DiedOf(cause));
+                }
+                commands.insert_resource(NextState(Some(GameState::Failed)));
+                return;
+            }
+            if reached {
+                commands.insert_resource(NextState(Some(GameState::Complete)));
+                return;
+            }
+            for player in late {
                 commands
                     .entity(player)
                     .insert(DiedOf(super::souls::DamageType::TimeOut));
@@ -168,54 +233,16 @@ fn end_ritual(
     }
 }
 
-fn move_person(mut people: Query<(&mut Transform, &Person, &mut TimeSoFar)>, time: Res<Time>) {
+fn move_person(mut people: Query<(&mut Transform, &Person, &mut TimeSoFar)>, time: Res<GameClock>) {
     let delta = time.delta_seconds();
 
-    for (mut transform, person, mut start_time) in &mut people {
-        start_time.0 += delta;
-        let delta = start_time.0;
-        let t = (delta / person.0).clamp(0., 1.);
-
-        let path = &person.1;
-
-        let Some(current_segment) = path.get(start_time.1) else {
-            warn!("No segment with id {} in {path:?}", start_time.1);
-            continue;
-        };
-
-        let Some(next_segment) = path.get(start_time.1 + 1) else {
-            continue;
-        };
-
-        let (current_segment, next_segment) = if next_segment.1 > t {
-            (current_segment, next_segment)
-        } else {
-            start_time.1 += 1;
-            let Some(new_segment) = path.get(start_time.1 + 1) else {
-                warn!(
-                    "Can't increment id - No next segment with id {} in {path:?}",
-                    start_time.1 + 1
-                );
-                continue;
-            };
-            info!(
-                "Incrementing ID to {} - path from {next_segment:?} to {new_segment:?}",
-                start_time.1
-            );
-            (next_segment, new_segment)
-        };
-
-        if next_segment.1 <= current_segment.1 {
-            error!("Next segment smaller than current segment");
-            continue;
-        }
-
-        let t = (t - current_segment.1) / (next_segment.1 - current_segment.1);
-        let direction = next_segment.0 - current_segment.0;
-        let point = direction * t + current_segment.0;
+    for (mut transform, person, mut time_so_far) in &mut people {
+        time_so_far.0 += delta;
+        let (point, direction) = person.1.travel(time_so_far.0, person.0);
 
         transform.translation = point.extend(0.);
-        let angle = direction.y.atan2(direction.x);
-        transform.rotation = Quat::from_rotation_z(angle);
+        if direction != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+        }
     }
 }
@@ -0,0 +1,173 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_vector_shapes::{
+    prelude::ShapePainter,
+    shapes::{DiscPainter, LinePainter},
+};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use leafwing_input_manager::prelude::ActionState;
+use serde::Deserialize;
+
+use crate::app_state::AppState;
+
+use super::{
+    actions::PlayerAction,
+    player::{Player, PlayerTargetReference},
+    ritual::{
+        pentagram_angle, Ritual, RitualProceeding, TimeSoFar, PENTAGRAM_STEPS, TRIANGLE_START,
+    },
+    schedule::InGameUpdate,
+    CurrentLevel,
+};
+
+pub fn ritual_minigame_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_performance)
+        .add_systems(InGameUpdate, perform_ritual)
+        .add_systems(PostUpdate, draw_ritual_minigame);
+}
+
+const TIMED_WINDOW: f32 = 0.4;
+const TRACE_WINDOW: f32 = 1.;
+const TRACE_RADIUS: f32 = 15.;
+const PENTAGRAM_RADIUS: f32 = 50.;
+const RITUAL_PASS_QUALITY: f32 = 0.4;
+const RITUAL_SCORE_BONUS: f32 = 50.;
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, PartialEq, Eq)]
+pub enum RitualMinigame {
+    Timed,
+    Trace,
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RitualPerformance {
+    pub mode: Option<RitualMinigame>,
+    pub steps: Vec<Option<f32>>,
+}
+
+impl RitualPerformance {
+    pub fn quality(&self) -> f32 {
+        self.steps.iter().flatten().sum::<f32>() / PENTAGRAM_STEPS as f32
+    }
+
+    pub fn passed(&self) -> bool {
+        self.mode.is_none() || self.quality() >= RITUAL_PASS_QUALITY
+    }
+
+    pub fn bonus(&self) -> usize {
+        if self.mode.is_some() {
+            (self.quality() * RITUAL_SCORE_BONUS).round() as usize
+        } else {
+            0
+        }
+    }
+}
+
+fn pentagram_point(center: Vec2, step: usize) -> Vec2 {
+    center + Vec2::from_angle(pentagram_angle(step)).rotate(Vec2::Y) * PENTAGRAM_RADIUS
+}
+
+fn reveal_time(ritual: &Ritual, step: usize) -> f32 {
+    ritual.start_time + TRIANGLE_START + step as f32
+}
+
+fn reset_performance(mut commands: Commands, level: Res<CurrentLevel>) {
+    commands.insert_resource(RitualPerformance {
+        mode: level.ritual_minigame,
+        steps: vec![None; PENTAGRAM_STEPS],
+    });
+}
+
+fn perform_ritual(
+    mut performance: ResMut<RitualPerformance>,
+    rituals: Query<(&Ritual, &TimeSoFar), With<RitualProceeding>>,
+    players: Query<(&ActionState<PlayerAction>, &PlayerTargetReference), With<Player>>,
+    targets: Query<&GlobalTransform>,
+) {
+    let Some(mode) = performance.mode else {
+        return;
+    };
+    let Ok((ritual, time)) = rituals.get_single() else {
+        return;
+    };
+    let pressed = players
+        .iter()
+        .any(|(actions, _)| actions.just_pressed(PlayerAction::Ritual));
+    let cursors = players
+        .iter()
+        .filter_map(|(_, target)| targets.get(target.0).ok())
+        .map(|v| v.translation().xy())
+        .collect::<Vec<_>>();
+
+    for step in 0..PENTAGRAM_STEPS {
+        if performance.steps[step].is_some() {
+            continue;
+        }
+        let offset = time.0 - reveal_time(ritual, step);
+        match mode {
+            RitualMinigame::Timed => {
+                if !pressed || offset.abs() > TIMED_WINDOW {
+                    continue;
+                }
+                performance.steps[step] = Some(1. - offset.abs() / TIMED_WINDOW);
+                break;
+            }
+            RitualMinigame::Trace => {
+                if !(0. ..=TRACE_WINDOW).contains(&offset) {
+                    continue;
+                }
+                let point = pentagram_point(ritual.position, step);
+                if cursors.iter().any(|v| v.distance(point) < TRACE_RADIUS) {
+                    performance.steps[step] = Some(1. - offset / TRACE_WINDOW);
+                }
+            }
+        }
+    }
+}
+
+fn draw_ritual_minigame(
+    performance: Res<RitualPerformance>,
+    rituals: Query<(&Ritual, &TimeSoFar), With<RitualProceeding>>,
+    mut painter: ShapePainter,
+) {
+    let Some(mode) = performance.mode else {
+        return;
+    };
+    painter.hollow = true;
+    painter.thickness = 2.;
+    for (ritual, time) in &rituals {
+        for step in 0..PENTAGRAM_STEPS {
+            let until = reveal_time(ritual, step) - time.0;
+            let hit = performance.steps.get(step).copied().flatten();
+            match mode {
+                RitualMinigame::Timed => {
+                    if hit.is_some() || !(-TIMED_WINDOW..=1.).contains(&until) {
+                        continue;
+                    }
+                    painter.color = crate::ui::colors::BAD_COLOR;
+                    painter.set_translation(ritual.position.extend(1.));
+                    painter.circle(ritual.radius * (1. + until.max(0.) * 2.));
+                }
+                RitualMinigame::Trace => {
+                    let point = pentagram_point(ritual.position, step);
+                    painter.set_translation(point.extend(1.));
+                    painter.color = match hit {
+                        Some(_) => crate::ui::colors::PRIMARY_COLOR,
+                        None if (-TRACE_WINDOW..=0.).contains(&until) => {
+                            crate::ui::colors::BAD_COLOR
+                        }
+                        None => crate::ui::colors::PRIMARY_COLOR.with_a(0.3),
+                    };
+                    painter.circle(TRACE_RADIUS);
+                    if step > 0 && hit.is_some() {
+                        painter.set_translation(Vec3::ZERO);
+                        painter.line(
+                            pentagram_point(ritual.position, step - 1).extend(1.),
+                            point.extend(1.),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use super::generate_level::LevelRoadSegment;
+
+const SAMPLES_PER_SPAN: usize = 16;
+const RAMP_TIME: f32 = 2.;
+
+#[derive(Debug, Clone)]
+pub struct RoadCurve {
+    points: Arc<[Vec2]>,
+    distances: Arc<[f32]>,
+}
+
+impl RoadCurve {
+    pub fn through(waypoints: &[Vec2]) -> Self {
+        let mut points = vec![];
+        for i in 0..waypoints.len().saturating_sub(1) {
+            let p0 = waypoints[i.saturating_sub(1)];
+            let p1 = waypoints[i];
+            let p2 = waypoints[i + 1];
+            let p3 = waypoints.get(i + 2).copied().unwrap_or(p2);
+            for sample in 0..SAMPLES_PER_SPAN {
+                let t = sample as f32 / SAMPLES_PER_SPAN as f32;
+                points.push(catmull_rom(p0, p1, p2, p3, t));
+            }
+        }
+        points.extend(waypoints.last());
+        Self::from_points(points)
+    }
+
+    fn from_points(points: Vec<Vec2>) -> Self {
+        let mut total = 0.;
+        let distances = points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                if i > 0 {
+                    total += point.distance(points[i - 1]);
+                }
+                total
+            })
+            .collect();
+        Self {
+            points: points.into(),
+            distances,
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or_default()
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.points.first().copied().unwrap_or_default()
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.points.last().copied().unwrap_or_default()
+    }
+
+    pub fn sample(&self, distance: f32) -> (Vec2, Vec2) {
+        let index = self
+            .distances
+            .partition_point(|v| *v <= distance)
+            .clamp(1, self.points.len().max(2) - 1);
+        let (Some(start), Some(end)) = (self.points.get(index - 1), self.points.get(index)) else {
+            return (self.start(), Vec2::X);
+        };
+        let span_start = self.distances[index - 1];
+        let span = self.distances[index] - span_start;
+        let t = if span > 0. {
+            ((distance - span_start) / span).clamp(0., 1.)
+        } else {
+            0.
+        };
+        (start.lerp(*end, t), (*end - *start).normalize_or_zero())
+    }
+
+    pub fn travel(&self, elapsed: f32, duration: f32) -> (Vec2, Vec2) {
+        self.sample(eased_distance(self.length(), elapsed, duration))
+    }
+
+    pub fn shortened(&self, amount: f32) -> Self {
+        let target = (self.length() - amount).max(0.);
+        let mut points = self
+            .points
+            .iter()
+            .zip(self.distances.iter())
+            .take_while(|(_, distance)| **distance < target)
+            .map(|(point, _)| *point)
+            .collect::<Vec<_>>();
+        points.push(self.sample(target).0);
+        Self::from_points(points)
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = LevelRoadSegment> + '_ {
+        self.points.windows(2).map(|pair| LevelRoadSegment {
+            start: pair[0],
+            end: pair[1],
+        })
+    }
+}
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2. * p1
+        + (p2 - p0) * t
+        + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+        + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+}
+
+// Trapezoidal speed profile - ease in and out, cruising in between, covering
+// exactly `length` by `duration`.
+fn eased_distance(length: f32, elapsed: f32, duration: f32) -> f32 {
+    if duration <= 0. {
+        return length;
+    }
+    let ramp = RAMP_TIME.min(duration / 4.);
+    let speed = length / (duration - ramp);
+    let elapsed = elapsed.clamp(0., duration);
+    if elapsed < ramp {
+        speed * elapsed * elapsed / (2. * ramp)
+    } else if elapsed > duration - ramp {
+        let remaining = duration - elapsed;
+        length - speed * remaining * remaining / (2. * ramp)
+    } else {
+        speed * (elapsed - ramp / 2.)
+    }
+}
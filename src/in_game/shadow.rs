@@ -11,7 +11,7 @@ use crate::{
     assets::WithMesh,
 };
 
-use super::schedule::InGamePreUpdate;
+use super::{lantern_bearer::Lantern, schedule::InGamePreUpdate};
 
 pub fn shadow_plugin(app: &mut ReloadableAppContents) {
     app.reset_resource::<ShadowCollisionGrid>()
@@ -24,10 +24,26 @@ pub fn shadow_plugin(app: &mut ReloadableAppContents) {
 const SHADOW_COLLISION_CELL_SIZE: f32 = 2000.;
 
 #[derive(Resource, Default)]
-struct ShadowCollisionGrid {
+pub(super) struct ShadowCollisionGrid {
     map: HashMap<(i32, i32), HashSet<Entity>>,
 }
 
+impl ShadowCollisionGrid {
+    pub(super) fn nearby(&self, position: Vec2, radius: f32) -> impl Iterator<Item = Entity> {
+        let bl = (position - Vec2::ONE * radius) / SHADOW_COLLISION_CELL_SIZE;
+        let tr = (position + Vec2::ONE * radius) / SHADOW_COLLISION_CELL_SIZE;
+        let mut entities = HashSet::new();
+        for x in (bl.x.floor() as i32)..=(tr.x.floor() as i32) {
+            for y in (bl.y.floor() as i32)..=(tr.y.floor() as i32) {
+                if let Some(cell) = self.map.get(&(x, y)) {
+                    entities.extend(cell.iter().copied());
+                }
+            }
+        }
+        entities.into_iter()
+    }
+}
+
 #[derive(Component)]
 pub struct Shadow {
     pub radius: f32,
@@ -59,12 +75,24 @@ fn draw_shadow(
     }
 }
 
+pub fn lit_by_lantern(position: Vec3, lanterns: &[(Vec3, f32)]) -> bool {
+    lanterns
+        .iter()
+        .any(|(lantern, radius)| lantern.distance(position) < *radius)
+}
+
 fn check_for_shadow(
     shadows: Query<(&GlobalTransform, &Shadow)>,
+    lanterns: Query<(&GlobalTransform, &Lantern)>,
     cells: Res<ShadowCollisionGrid>,
     check_for_shadow: Query<(Entity, &GlobalTransform), With<CheckForShadow>>,
     mut commands: Commands,
 ) {
+    let lanterns = lanterns
+        .iter()
+        .map(|(transform, lantern)| (transform.translation(), lantern.radius))
+        .collect::<Box<[_]>>();
+
     for (entity, check) in check_for_shadow.iter() {
         let check_position = check.translation();
         let cell = (
@@ -78,7 +106,7 @@ fn check_for_shadow(
                 .any(|(transform, shadow)| {
                     let position = transform.translation();
                     let distance = position.distance(check_position);
-                    distance < shadow.radius
+                    distance < shadow.radius && !lit_by_lantern(position, &lanterns)
                 })
         } else {
             false
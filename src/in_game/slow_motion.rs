@@ -0,0 +1,119 @@
+use bevy::{ecs::query::Has, prelude::*};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::app_state::AppState;
+
+use super::{
+    actions::PlayerAction,
+    beat::LevelSong,
+    game_clock::GameClock,
+    game_state::GameState,
+    player::Player,
+    schedule::InGameUpdate,
+    souls::{Damage, DamageType, MaxSouls, Souls},
+    timeline::MusicLayer,
+};
+
+pub fn slow_motion_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_slow_motion)
+        .add_systems(OnExit(GameState::InGame), restore_time_scale)
+        .add_systems(
+            InGameUpdate,
+            (detect_near_death, slow_motion_aim, apply_time_scale).chain(),
+        );
+}
+
+const SLOW_MOTION_SCALE: f32 = 0.3;
+const SLOW_MOTION_SOUL_COST: f32 = 8.;
+const SLOW_MOTION_MIN_SOULS: f32 = 5.;
+const NEAR_DEATH_RATIO: f32 = 0.2;
+const NEAR_DEATH_PULSE: f32 = 0.6;
+pub const EXPLOSION_PULSE: f32 = 0.4;
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SlowMotionPulse(pub f32);
+
+#[derive(Resource, Default, Debug)]
+pub struct SlowMotion {
+    pub aiming: bool,
+    pub pulse: f32,
+}
+
+#[derive(Component)]
+struct NearDeath;
+
+fn reset_slow_motion(mut commands: Commands) {
+    commands.insert_resource(SlowMotion::default());
+}
+
+fn detect_near_death(
+    players: Query<(Entity, &Souls, &MaxSouls, Has<NearDeath>), With<Player>>,
+    mut pulses: EventWriter<SlowMotionPulse>,
+    mut commands: Commands,
+) {
+    for (entity, souls, max_souls, near_death) in &players {
+        let low = souls.0 < max_souls.0 * NEAR_DEATH_RATIO;
+        if low && !near_death {
+            commands.entity(entity).insert(NearDeath);
+            pulses.send(SlowMotionPulse(NEAR_DEATH_PULSE));
+        } else if !low && near_death {
+            commands.entity(entity).remove::<NearDeath>();
+        }
+    }
+}
+
+// The soul cost is paid in real time, otherwise slowing time would make itself cheaper.
+fn slow_motion_aim(
+    players: Query<(Entity, &ActionState<PlayerAction>, &Souls), With<Player>>,
+    mut slow_motion: ResMut<SlowMotion>,
+    mut damage: EventWriter<Damage>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    slow_motion.aiming = false;
+    for (entity, actions, souls) in &players {
+        if !actions.pressed(PlayerAction::SlowTime) || souls.0 <= SLOW_MOTION_MIN_SOULS {
+            continue;
+        }
+        damage.send(Damage {
+            entity,
+            amount: (SLOW_MOTION_SOUL_COST * delta).min(souls.0 - SLOW_MOTION_MIN_SOULS),
+            damage_type: DamageType::SlowMotion,
+        });
+        slow_motion.aiming = true;
+    }
+}
+
+fn apply_time_scale(
+    mut slow_motion: ResMut<SlowMotion>,
+    mut pulses: EventReader<SlowMotionPulse>,
+    mut clock: ResMut<GameClock>,
+    songs: Query<&AudioSink, Or<(With<LevelSong>, With<MusicLayer>)>>,
+    time: Res<Time>,
+) {
+    for SlowMotionPulse(duration) in pulses.iter() {
+        slow_motion.pulse = slow_motion.pulse.max(*duration);
+    }
+    slow_motion.pulse = (slow_motion.pulse - time.delta_seconds()).max(0.);
+
+    clock.scale = if slow_motion.aiming || slow_motion.pulse > 0. {
+        SLOW_MOTION_SCALE
+    } else {
+        1.
+    };
+
+    for song in &songs {
+        song.set_speed(clock.scale);
+    }
+}
+
+fn restore_time_scale(
+    mut clock: ResMut<GameClock>,
+    songs: Query<&AudioSink, Or<(With<LevelSong>, With<MusicLayer>)>>,
+) {
+    clock.scale = 1.;
+    for song in &songs {
+        song.set_speed(1.);
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use crate::app_state::AppState;
+
+use super::{
+    angelic_archers::AngelicArrow,
+    checkpoints::Checkpoints,
+    co_op::GameMode,
+    danger::{Danger, DangerExists, DangerSpawner, Restlessness, Shooting, SpawnTime},
+    game_clock::GameClock,
+    player::{end_game, Player},
+    schedule::InGameUpdate,
+};
+
+pub fn snapshot_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_snapshots)
+        .add_systems(
+            InGameUpdate,
+            (snapshot_new_checkpoints, drop_unused_snapshots).chain(),
+        )
+        .add_systems(PostUpdate, restore_snapshot.after(end_game));
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RestoreSnapshot(pub usize);
+
+#[derive(Debug, Default)]
+struct DangerSnapshot {
+    transform: Transform,
+    restlessness: Option<f32>,
+    age: Option<f32>,
+    since_shot: Option<f32>,
+}
+
+// Snapshots only rewind the dangers around the players - the song, the person
+// and the level timeline keep going. Streamed dangers spawned since the snapshot
+// are returned to their spawners, and ones that streamed out since then come
+// back through their spawners rather than being re-created here.
+#[derive(Debug, Default)]
+struct WorldSnapshot {
+    dangers: HashMap<Entity, DangerSnapshot>,
+    projectiles: HashSet<Entity>,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct WorldSnapshots {
+    next: usize,
+    snapshots: HashMap<usize, WorldSnapshot>,
+}
+
+fn reset_snapshots(mut commands: Commands) {
+    commands.insert_resource(WorldSnapshots::default());
+}
+
+fn snapshot_new_checkpoints(
+    mode: Res<GameMode>,
+    mut snapshots: ResMut<WorldSnapshots>,
+    mut players: Query<&mut Checkpoints, (With<Player>, Changed<Checkpoints>)>,
+    dangers: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&Restlessness>,
+            Option<&SpawnTime>,
+            Option<&Shooting>,
+        ),
+        (With<Danger>, Without<AngelicArrow>),
+    >,
+    projectiles: Query<Entity, With<AngelicArrow>>,
+    time: Res<GameClock>,
+) {
+    if !mode.save_points {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    for mut checkpoints in &mut players {
+        let Some(index) = checkpoints
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.snapshot.is_none())
+        else {
+            continue;
+        };
+
+        let snapshot = WorldSnapshot {
+            dangers: dangers
+                .iter()
+                .map(|(entity, transform, restlessness, spawn_time, shooting)| {
+                    (
+                        entity,
+                        DangerSnapshot {
+                            transform: *transform,
+                            restlessness: restlessness.map(|v| v.current_restlessness),
+                            age: spawn_time.map(|v| now - v.0),
+                            since_shot: shooting.map(|v| now - v.last_shot),
+                        },
+                    )
+                })
+                .collect(),
+            projectiles: projectiles.iter().collect(),
+        };
+
+        let id = snapshots.next;
+        snapshots.next += 1;
+        snapshots.snapshots.insert(id, snapshot);
+        checkpoints.checkpoints[index].snapshot = Some(id);
+    }
+}
+
+fn drop_unused_snapshots(
+    mut snapshots: ResMut<WorldSnapshots>,
+    players: Query<&Checkpoints, With<Player>>,
+    changed: Query<(), (With<Player>, Changed<Checkpoints>)>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    let used = players
+        .iter()
+        .flat_map(|checkpoints| checkpoints.checkpoints.iter())
+        .filter_map(|checkpoint| checkpoint.snapshot)
+        .collect::<HashSet<_>>();
+    snapshots.snapshots.retain(|id, _| used.contains(id));
+}
+
+fn restore_snapshot(
+    mut events: EventReader<RestoreSnapshot>,
+    mut snapshots: ResMut<WorldSnapshots>,
+    mut dangers: Query<
+        (
+            Entity,
+            &mut Transform,
+            Option<&mut Restlessness>,
+            Option<&mut SpawnTime>,
+            Option<&mut Shooting>,
+            Option<&DangerSpawner>,
+        ),
+        (With<Danger>, Without<AngelicArrow>),
+    >,
+    projectiles: Query<Entity, With<AngelicArrow>>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let now = time.elapsed_seconds();
+    for RestoreSnapshot(id) in events.iter() {
+        let Some(snapshot) = snapshots.snapshots.remove(id) else {
+            continue;
+        };
+        for (entity, mut transform, restlessness, spawn_time, shooting, spawner) in &mut dangers {
+            let Some(saved) = snapshot.dangers.get(&entity) else {
+                if let Some(DangerSpawner(spawner)) = spawner {
+                    commands.entity(entity).despawn_recursive();
+                    if let Some(mut spawner) = commands.get_entity(*spawner) {
+                        spawner.remove::<DangerExists>();
+                    }
+                }
+                continue;
+            };
+            *transform = saved.transform;
+            if let (Some(mut restlessness), Some(saved)) = (restlessness, saved.restlessness) {
+                restlessness.current_restlessness = saved;
+            }
+            if let (Some(mut spawn_time), Some(age)) = (spawn_time, saved.age) {
+                spawn_time.0 = now - age;
+            }
+            if let (Some(mut shooting), Some(since_shot)) = (shooting, saved.since_shot) {
+                shooting.last_shot = now - since_shot;
+            }
+        }
+        for projectile in &projectiles {
+            if !snapshot.projectiles.contains(&projectile) {
+                commands.entity(projectile).despawn_recursive();
+            }
+        }
+    }
+}
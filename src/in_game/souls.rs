@@ -3,6 +3,7 @@ use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 
 use super::{
     danger::{Danger, DangerType},
+    game_clock::GameClock,
     game_state::TemporaryIgnore,
     player::Player,
     schedule::InGameUpdate,
@@ -43,6 +44,12 @@ pub enum DamageType {
     Sunlight,
     Danger(DangerType),
     TimeOut,
+    Boss,
+    ObjectiveFailed,
+    PersonLost,
+    RitualFailed,
+    SlowMotion,
+    Cloak,
 }
 
 #[derive(Event, Clone, Copy, Debug)]
@@ -54,7 +61,7 @@ pub struct Death {
 pub fn sun_sensitivity(
     sensitives: Query<(Entity, &SunSensitivity), Without<InShadow>>,
     mut writer: EventWriter<Damage>,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let delta = time.delta_seconds();
     for (entity, sensitivity) in sensitives.iter() {
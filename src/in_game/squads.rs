@@ -0,0 +1,127 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::query::Has, math::Vec3Swizzles, prelude::*};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use super::{
+    danger::{DangerSpawner, Shot},
+    movement::{movement, Flocking},
+    schedule::InGameUpdate,
+};
+
+pub const SQUAD_SPAWN_RADIUS: f32 = 40.;
+pub const SQUAD_SURROUND_RADIUS: f32 = 25.;
+pub const SQUAD_SHOT_INTERVAL: f32 = 0.4;
+
+const SEPARATION_DISTANCE: f32 = 35.;
+const SEPARATION_WEIGHT: f32 = 1.5;
+const COHESION_DISTANCE: f32 = 80.;
+const COHESION_WEIGHT: f32 = 0.8;
+
+pub fn squad_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(
+        InGameUpdate,
+        (join_squads, update_squads, flock_squads)
+            .chain()
+            .before(movement),
+    );
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SquadMember(pub Entity);
+
+#[derive(Component, Default, Debug)]
+pub struct SquadBlackboard {
+    pub leader: Option<Entity>,
+    pub members: Vec<Entity>,
+    pub last_shot: f32,
+}
+
+impl SquadBlackboard {
+    pub fn slot_offset(&self, member: Entity) -> Vec2 {
+        let Some(index) = self.members.iter().position(|v| *v == member) else {
+            return Vec2::ZERO;
+        };
+        let angle = (index as f32) * TAU / (self.members.len() as f32);
+        Vec2::new(angle.cos(), angle.sin()) * SQUAD_SURROUND_RADIUS
+    }
+}
+
+#[derive(Component)]
+struct JoinedSquad;
+
+fn join_squads(
+    members: Query<(Entity, &SquadMember), (With<DangerSpawner>, Without<JoinedSquad>)>,
+    mut squads: Query<&mut SquadBlackboard>,
+    mut commands: Commands,
+) {
+    for (entity, member) in &members {
+        commands.entity(entity).insert(JoinedSquad);
+        let Ok(mut squad) = squads.get_mut(member.0) else {
+            continue;
+        };
+        squad.members.push(entity);
+        if squad.leader.is_none() {
+            squad.leader = Some(entity);
+        }
+    }
+}
+
+fn update_squads(mut squads: Query<&mut SquadBlackboard>, members: Query<(), With<JoinedSquad>>) {
+    for mut squad in &mut squads {
+        squad.members.retain(|v| members.contains(*v));
+        let leader_alive = squad.leader.map(|v| members.contains(v)).unwrap_or(false);
+        if !leader_alive {
+            squad.leader = squad.members.first().copied();
+        }
+    }
+}
+
+fn flock_squads(
+    squads: Query<&SquadBlackboard>,
+    members: Query<(Entity, &GlobalTransform, &SquadMember, Has<Shot>)>,
+    mut commands: Commands,
+) {
+    for (entity, transform, member, has_shot) in &members {
+        let Ok(squad) = squads.get(member.0) else {
+            continue;
+        };
+        if has_shot {
+            commands.entity(entity).remove::<Flocking>();
+            continue;
+        }
+        let position = transform.translation().xy();
+
+        let separation = squad
+            .members
+            .iter()
+            .filter(|v| **v != entity)
+            .filter_map(|v| members.get(*v).ok())
+            .map(|(_, other, _, _)| position - other.translation().xy())
+            .filter(|v| v.length() < SEPARATION_DISTANCE)
+            .map(|v| v.normalize_or_zero() * (1. - v.length() / SEPARATION_DISTANCE))
+            .sum::<Vec2>();
+
+        let cohesion = match squad.leader {
+            Some(leader) if leader != entity => members
+                .get(leader)
+                .ok()
+                .map(|(_, leader, _, _)| leader.translation().xy() - position)
+                .filter(|v| v.length() > COHESION_DISTANCE)
+                .map(|v| v.normalize_or_zero())
+                .unwrap_or_default(),
+            _ => Vec2::ZERO,
+        };
+
+        commands.entity(entity).insert(Flocking(
+            separation * SEPARATION_WEIGHT + cohesion * COHESION_WEIGHT,
+        ));
+    }
+}
+
+pub fn squad_offsets(size: usize) -> impl Iterator<Item = Vec2> {
+    (0..size).map(move |i| {
+        let angle = (i as f32) * TAU / (size as f32);
+        Vec2::new(angle.cos(), angle.sin()) * SQUAD_SPAWN_RADIUS
+    })
+}
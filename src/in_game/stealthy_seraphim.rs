@@ -11,10 +11,16 @@ use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 use crate::assets::WithMesh;
 
 use super::{
-    danger::{Chase, Chasing, Danger, Resting, Restlessness, Shoot, Shooting, Shot, SpawnTime},
+    danger::{
+        Chase, Chasing, Danger, Patrol, Patrolling, Resting, Restlessness, Shoot, Shooting, Shot,
+        SpawnTime,
+    },
+    game_clock::GameClock,
     movement::{CanMove, Moving},
     schedule::InGameUpdate,
     souls::LethalTouch,
+    squads::{SquadBlackboard, SquadMember, SQUAD_SHOT_INTERVAL},
+    teleport::PathInteraction,
 };
 
 #[derive(Component)]
@@ -30,13 +36,14 @@ pub fn stealthy_seraphim_plugin(app: &mut ReloadableAppContents) {
 fn spawn_stealthy_seraphim(
     dangers: Query<Entity, (With<StealthySeraphim>, Without<Danger>)>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<GameClock>,
 ) {
     let now = time.elapsed_seconds();
     for danger in &dangers {
         commands.entity(danger).insert((
             Name::new("Stealthy Seraphim"),
             Danger(10.),
+            PathInteraction::Damage(15.),
             CanMove { move_speed: 200. },
             SpawnTime(now),
             Restlessness {
@@ -72,6 +79,7 @@ fn spawn_stealthy_seraphim(
                         target_distance: 90.,
                     },
                 )
+                .when(Patrol, Patrolling)
                 .otherwise(Resting),
             WithMesh::StealthySeraphim,
             LethalTouch,
@@ -84,12 +92,25 @@ fn spawn_stealthy_seraphim(
 struct IsShot;
 
 fn shooting(
-    dangers: Query<(Entity, &Shot, &Transform), (With<StealthySeraphim>, Without<IsShot>)>,
+    dangers: Query<
+        (Entity, &Shot, &Transform, Option<&SquadMember>),
+        (With<StealthySeraphim>, Without<IsShot>),
+    >,
+    mut squads: Query<&mut SquadBlackboard>,
     mut commands: Commands,
+    time: Res<GameClock>,
 ) {
-    for (danger, shot, transform) in &dangers {
+    let now = time.elapsed_seconds();
+    for (danger, shot, transform, squad_member) in &dangers {
         let start = transform.translation;
-        let end = shot.target_point;
+        let mut end = shot.target_point;
+        if let Some(mut squad) = squad_member.and_then(|v| squads.get_mut(v.0).ok()) {
+            if now - squad.last_shot < SQUAD_SHOT_INTERVAL {
+                continue;
+            }
+            squad.last_shot = now;
+            end += squad.slot_offset(danger).extend(0.);
+        }
         let start_rotation = transform.rotation.z;
         let direction = end.xy() - start.xy();
         let angle = direction.y.atan2(direction.x);
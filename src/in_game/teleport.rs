@@ -1,20 +1,33 @@
 use std::time::Duration;
 
+use super::danger::{Danger, DangerType};
+use super::game_clock::GameClock;
 use super::schedule::InGameUpdate;
 use super::shadow::InShadow;
+use super::souls::{take_damage, Damage, DamageType, Souls};
 use super::{game_state::TemporaryIgnore, player::*};
 
 use bevy::ecs::query::Has;
+use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
+use bevy_inspector_egui::InspectorOptions;
 use bevy_tweening::lens::*;
 use bevy_tweening::*;
+use serde::Deserialize;
 
 use dexterous_developer::{ReloadableApp, ReloadableAppContents};
 
 pub fn teleport_plugin(app: &mut ReloadableAppContents) {
     app.add_systems(
         InGameUpdate,
-        (trigger_teleport, validate_teleporation_target),
+        (
+            (trigger_teleport, apply_deferred, sweep_teleport_path)
+                .chain()
+                .before(take_damage),
+            validate_teleporation_target,
+            regenerate_teleport_energy,
+            unlock_teleport,
+        ),
     )
     .add_systems(PostUpdate, (clear_teleport, run_teleport));
 }
@@ -34,6 +47,54 @@ impl Default for CanTeleport {
     }
 }
 
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(default)]
+pub struct TeleportEnergySettings {
+    pub max_energy: f32,
+    pub base_cost: f32,
+    pub cost_per_unit: f32,
+    pub regen_per_second: f32,
+}
+
+impl Default for TeleportEnergySettings {
+    fn default() -> Self {
+        Self {
+            max_energy: 100.,
+            base_cost: 10.,
+            cost_per_unit: 0.1,
+            regen_per_second: 25.,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct TeleportEnergy {
+    pub current: f32,
+    pub settings: TeleportEnergySettings,
+}
+
+impl TeleportEnergy {
+    pub fn new(settings: TeleportEnergySettings) -> Self {
+        Self {
+            current: settings.max_energy,
+            settings,
+        }
+    }
+
+    pub fn cost(&self, distance: f32) -> f32 {
+        self.settings.base_cost + distance * self.settings.cost_per_unit
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub enum PathInteraction {
+    Block,
+    Damage(f32),
+    Kill,
+}
+
+const BOUNCE_DISTANCE: f32 = 20.;
+
 #[derive(Component, Debug, Clone)]
 #[component(storage = "SparseSet")]
 pub struct TargetInRange;
@@ -46,6 +107,23 @@ pub struct Teleporting;
 #[component(storage = "SparseSet")]
 pub struct StartTeleport(pub Vec3);
 
+#[derive(Debug, Component, Clone)]
+#[component(storage = "SparseSet")]
+pub struct TeleportLocked(pub f32);
+
+fn unlock_teleport(
+    mut locked: Query<(Entity, &mut TeleportLocked)>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    for (entity, mut lock) in &mut locked {
+        lock.0 -= time.delta_seconds();
+        if lock.0 <= 0. {
+            commands.entity(entity).remove::<TeleportLocked>();
+        }
+    }
+}
+
 pub fn trigger_teleport(
     targets: Query<
         &Transform,
@@ -56,17 +134,23 @@ pub fn trigger_teleport(
             Without<TemporaryIgnore>,
         ),
     >,
-    teleporters: Query<
-        (Entity, &PlayerTargetReference),
+    mut teleporters: Query<
+        (
+            Entity,
+            &Transform,
+            &PlayerTargetReference,
+            Option<&mut TeleportEnergy>,
+        ),
         (
             With<Teleporting>,
             Without<StartTeleport>,
             Without<TemporaryIgnore>,
+            Without<PlayerTarget>,
         ),
     >,
     mut commands: Commands,
 ) {
-    for (teleporter, target) in teleporters.iter() {
+    for (teleporter, transform, target, energy) in teleporters.iter_mut() {
         println!("Handling teleport");
         let Some(target) = targets.get(target.0).ok() else {
             commands.entity(teleporter).remove::<Teleporting>();
@@ -75,6 +159,15 @@ pub fn trigger_teleport(
 
         let next_position = target.translation;
 
+        if let Some(mut energy) = energy {
+            let cost = energy.cost(transform.translation.distance(next_position));
+            if energy.current < cost {
+                commands.entity(teleporter).remove::<Teleporting>();
+                continue;
+            }
+            energy.current -= cost;
+        }
+
         commands
             .entity(teleporter)
             .insert(StartTeleport(next_position));
@@ -149,7 +242,83 @@ pub fn run_teleport(
     }
 }
 
-const TELEPORT_COMPLETED_EVENT: u64 = 22;
+pub fn sweep_teleport_path(
+    mut teleporters: Query<
+        (Entity, &Transform, &mut StartTeleport, &Souls),
+        (With<Player>, Without<TemporaryIgnore>),
+    >,
+    dangers: Query<
+        (&GlobalTransform, &Danger, &DangerType, &PathInteraction),
+        Without<TemporaryIgnore>,
+    >,
+    mut damage: EventWriter<Damage>,
+    mut commands: Commands,
+) {
+    for (entity, transform, mut start_teleport, souls) in &mut teleporters {
+        let start = transform.translation.xy();
+        let path = start_teleport.0.xy() - start;
+        let length = path.length();
+        if length <= 0. {
+            continue;
+        }
+        let direction = path / length;
+
+        let mut hits = dangers
+            .iter()
+            .filter_map(|(danger_transform, radius, danger_type, interaction)| {
+                let position = danger_transform.translation().xy();
+                let along = (position - start).dot(direction).clamp(0., length);
+                let closest = start + direction * along;
+                (closest.distance(position) < radius.0).then_some((
+                    along,
+                    radius.0,
+                    *danger_type,
+                    *interaction,
+                ))
+            })
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut remaining = souls.0;
+        for (along, radius, danger_type, interaction) in hits {
+            match interaction {
+                PathInteraction::Block => {
+                    let stop = along - radius - BOUNCE_DISTANCE;
+                    let bounce = if stop > 0. {
+                        start + direction * stop
+                    } else {
+                        start - direction * BOUNCE_DISTANCE
+                    };
+                    start_teleport.0 = bounce.extend(start_teleport.0.z);
+                    break;
+                }
+                PathInteraction::Damage(amount) => {
+                    damage.send(Damage {
+                        entity,
+                        amount,
+                        damage_type: DamageType::Danger(danger_type),
+                    });
+                    remaining -= amount;
+                    if remaining <= 0. {
+                        commands.entity(entity).remove::<StartTeleport>();
+                        break;
+                    }
+                }
+                PathInteraction::Kill => {
+                    damage.send(Damage {
+                        entity,
+                        amount: remaining.max(0.) + 1.,
+                        damage_type: DamageType::Danger(danger_type),
+                    });
+                    commands.entity(entity).remove::<StartTeleport>();
+                    break;
+                }
+            }
+        }
+    }
+}
+
+pub const TELEPORT_COMPLETED_EVENT: u64 = 22;
 
 pub fn clear_teleport(
     teleporters: Query<Entity>,
@@ -169,6 +338,17 @@ pub fn clear_teleport(
     }
 }
 
+pub fn regenerate_teleport_energy(
+    mut teleporters: Query<&mut TeleportEnergy, (With<InShadow>, Without<TemporaryIgnore>)>,
+    time: Res<GameClock>,
+) {
+    let delta = time.delta_seconds();
+    for mut energy in &mut teleporters {
+        energy.current = (energy.current + energy.settings.regen_per_second * delta)
+            .min(energy.settings.max_energy);
+    }
+}
+
 pub fn validate_teleporation_target(
     target: Query<(Entity, &GlobalTransform, &PlayerTarget)>,
     parent: Query<(&GlobalTransform, &CanTeleport), With<PlayerTargetReference>>,
@@ -0,0 +1,292 @@
+use bevy::{
+    audio::{Volume, VolumeLevel},
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_vector_shapes::{prelude::ShapePainter, shapes::LinePainter};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use serde::{Deserialize, Serialize};
+
+use crate::{app_state::AppState, ui::colors::DEFAULT_AMBIENT};
+
+use super::{
+    checkpoints::{Checkpoints, StoredCheckpoint},
+    danger::DangerType,
+    game_clock::GameClock,
+    in_game_text::ActiveDialogue,
+    player::Player,
+    schedule::{InGamePostUpdate, InGameUpdate},
+    souls::{Damage, DamageType, MaxSouls, Souls, SunSensitivity},
+    teleport::TeleportLocked,
+    CurrentLevel, InGame,
+};
+
+pub fn timeline_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_timeline)
+        .add_systems(InGameUpdate, (run_timeline, sweep_sun))
+        .add_systems(InGamePostUpdate, run_level_actions)
+        .add_systems(PostUpdate, draw_sun_sweep);
+}
+
+const DANGER_GROUP_SPREAD: f32 = 30.;
+const SUN_SWEEP_START_DISTANCE: f32 = 800.;
+const SUN_SWEEP_LENGTH: f32 = 4000.;
+const SUN_SWEEP_INTENSITY: f32 = 3.;
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Serialize, Debug)]
+#[serde(tag = "type")]
+pub enum LevelAction {
+    SpawnDangers {
+        danger: DangerType,
+        count: usize,
+        offset: Vec2,
+    },
+    Ambient {
+        brightness: f32,
+    },
+    SunSweep {
+        direction: Vec2,
+        speed: f32,
+        width: f32,
+        duration: f32,
+    },
+    Dialogue {
+        text: String,
+        duration: f32,
+    },
+    MusicLayer {
+        song: String,
+        volume: f32,
+    },
+    GrantCheckpoint,
+    LockTeleport {
+        duration: f32,
+    },
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+pub struct TimelineEvent {
+    pub time: f32,
+    pub action: LevelAction,
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct RunLevelAction {
+    pub action: LevelAction,
+    pub player: Option<Entity>,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Timeline {
+    pub elapsed: f32,
+}
+
+#[derive(Component)]
+struct SunSweep {
+    origin: Vec2,
+    direction: Vec2,
+    speed: f32,
+    width: f32,
+    remaining: f32,
+    travelled: f32,
+}
+
+#[derive(Component)]
+pub struct MusicLayer(String);
+
+fn reset_timeline(mut commands: Commands) {
+    commands.insert_resource(Timeline::default());
+    commands.insert_resource(ActiveDialogue::default());
+    commands.insert_resource(DEFAULT_AMBIENT);
+}
+
+fn run_timeline(
+    mut timeline: ResMut<Timeline>,
+    level: Res<CurrentLevel>,
+    mut actions: EventWriter<RunLevelAction>,
+    time: Res<GameClock>,
+) {
+    let previous = timeline.elapsed;
+    timeline.elapsed += time.delta_seconds();
+    let now = timeline.elapsed;
+
+    for event in level.timeline.iter() {
+        if event.time >= previous && event.time < now {
+            actions.send(RunLevelAction {
+                action: event.action.clone(),
+                player: None,
+            });
+        }
+    }
+}
+
+fn run_level_actions(
+    mut actions: EventReader<RunLevelAction>,
+    mut players: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Souls,
+            &MaxSouls,
+            Option<&mut Checkpoints>,
+        ),
+        With<Player>,
+    >,
+    layers: Query<(Entity, &MusicLayer, Option<&AudioSink>)>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    for RunLevelAction { action, player } in actions.iter() {
+        let positions = players
+            .iter()
+            .filter(|(entity, ..)| player.map(|player| player == *entity).unwrap_or(true))
+            .map(|(_, transform, ..)| transform.translation().xy())
+            .collect::<Vec<_>>();
+        let center = positions.iter().sum::<Vec2>() / positions.len().max(1) as f32;
+
+        match action {
+            LevelAction::SpawnDangers {
+                danger,
+                count,
+                offset,
+            } => {
+                let spread = if *count > 1 { DANGER_GROUP_SPREAD } else { 0. };
+                for i in 0..*count {
+                    let angle = i as f32 * std::f32::consts::TAU / *count as f32;
+                    let position = center + *offset + Vec2::from_angle(angle) * spread;
+                    commands.spawn((
+                        SpatialBundle {
+                            transform: Transform::from_translation(position.extend(0.)),
+                            ..Default::default()
+                        },
+                        *danger,
+                        InGame,
+                    ));
+                }
+            }
+            LevelAction::Ambient { brightness } => {
+                commands.insert_resource(AmbientLight {
+                    brightness: *brightness,
+                    ..DEFAULT_AMBIENT
+                });
+            }
+            LevelAction::SunSweep {
+                direction,
+                speed,
+                width,
+                duration,
+            } => {
+                let direction = direction.normalize_or_zero();
+                commands.spawn((
+                    SunSweep {
+                        origin: center - direction * SUN_SWEEP_START_DISTANCE,
+                        direction,
+                        speed: *speed,
+                        width: *width,
+                        remaining: *duration,
+                        travelled: 0.,
+                    },
+                    InGame,
+                ));
+            }
+            LevelAction::Dialogue { text, duration } => {
+                dialogue.text = text.clone();
+                dialogue.remaining = *duration;
+                dialogue.serial += 1;
+            }
+            LevelAction::MusicLayer { song, volume } => {
+                for (entity, layer, sink) in &layers {
+                    if layer.0 != *song {
+                        continue;
+                    }
+                    if let Some(sink) = sink {
+                        sink.stop();
+                    }
+                    commands.entity(entity).despawn_recursive();
+                }
+                if *volume > 0. {
+                    commands.spawn((
+                        AudioBundle {
+                            source: asset_server.load(song.as_str()),
+                            settings: PlaybackSettings {
+                                volume: Volume::Absolute(VolumeLevel::new(*volume)),
+                                ..Default::default()
+                            },
+                        },
+                        MusicLayer(song.clone()),
+                        InGame,
+                    ));
+                }
+            }
+            LevelAction::GrantCheckpoint => {
+                for (entity, transform, souls, max_souls, checkpoints) in &mut players {
+                    if player.map(|player| player != entity).unwrap_or(false) {
+                        continue;
+                    }
+                    let Some(mut checkpoints) = checkpoints else {
+                        continue;
+                    };
+                    checkpoints.store(StoredCheckpoint {
+                        position: transform.translation(),
+                        souls: *souls,
+                        max_souls: *max_souls,
+                        snapshot: None,
+                    });
+                }
+            }
+            LevelAction::LockTeleport { duration } => {
+                for (entity, ..) in &players {
+                    if player.map(|player| player != entity).unwrap_or(false) {
+                        continue;
+                    }
+                    if *duration > 0. {
+                        commands.entity(entity).insert(TeleportLocked(*duration));
+                    } else {
+                        commands.entity(entity).remove::<TeleportLocked>();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn sweep_sun(
+    mut sweeps: Query<(Entity, &mut SunSweep)>,
+    players: Query<(Entity, &GlobalTransform, &SunSensitivity), With<Player>>,
+    mut damage: EventWriter<Damage>,
+    mut commands: Commands,
+    time: Res<GameClock>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut sweep) in &mut sweeps {
+        sweep.remaining -= delta;
+        if sweep.remaining <= 0. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        sweep.travelled += sweep.speed * delta;
+        for (player, transform, sensitivity) in &players {
+            let along = (transform.translation().xy() - sweep.origin).dot(sweep.direction);
+            if (along - sweep.travelled).abs() > sweep.width / 2. {
+                continue;
+            }
+            damage.send(Damage {
+                entity: player,
+                amount: sensitivity.0 * SUN_SWEEP_INTENSITY * delta,
+                damage_type: DamageType::Sunlight,
+            });
+        }
+    }
+}
+
+fn draw_sun_sweep(sweeps: Query<&SunSweep>, mut painter: ShapePainter) {
+    for sweep in &sweeps {
+        let center = sweep.origin + sweep.direction * sweep.travelled;
+        let across = sweep.direction.perp() * SUN_SWEEP_LENGTH / 2.;
+        painter.thickness = sweep.width;
+        painter.color = Color::rgba(1., 0.9, 0.5, 0.3);
+        painter.line((center - across).extend(-1.), (center + across).extend(-1.));
+    }
+}
@@ -0,0 +1,301 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_inspector_egui::InspectorOptions;
+use bevy_vector_shapes::{
+    prelude::ShapePainter,
+    shapes::{DiscPainter, LinePainter},
+};
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+use serde::Deserialize;
+
+use crate::app_state::DrawDebugGizmos;
+
+use super::{
+    game_clock::GameClock,
+    generate_level::{LevelSections, LevelShape},
+    player::Player,
+    schedule::InGameUpdate,
+    timeline::{LevelAction, RunLevelAction},
+    CurrentLevel, InGame,
+};
+
+pub fn trigger_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(InGameUpdate, check_triggers)
+        .add_systems(Update, draw_triggers);
+}
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum TriggerWhen {
+    #[default]
+    Enter,
+    Leave,
+    Stay,
+}
+
+#[derive(Clone, Copy, Reflect, InspectorOptions, Deserialize, Debug, Default)]
+pub enum TriggerAnchor {
+    #[default]
+    PlayerStart,
+    Crossroads,
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum TriggerShape {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    Quad {
+        bottom_left: Vec2,
+        top_left: Vec2,
+        top_right: Vec2,
+        bottom_right: Vec2,
+    },
+}
+
+impl TriggerShape {
+    fn offset(&self, by: Vec2) -> Self {
+        match self {
+            TriggerShape::Circle { center, radius } => TriggerShape::Circle {
+                center: *center + by,
+                radius: *radius,
+            },
+            TriggerShape::Quad {
+                bottom_left,
+                top_left,
+                top_right,
+                bottom_right,
+            } => TriggerShape::Quad {
+                bottom_left: *bottom_left + by,
+                top_left: *top_left + by,
+                top_right: *top_right + by,
+                bottom_right: *bottom_right + by,
+            },
+        }
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        match self {
+            TriggerShape::Circle { center, radius } => center.distance(point) <= *radius,
+            TriggerShape::Quad {
+                bottom_left,
+                top_left,
+                top_right,
+                bottom_right,
+            } => {
+                let corners = [*bottom_left, *top_left, *top_right, *bottom_right];
+                let sides = (0..4)
+                    .map(|i| {
+                        let a = corners[i];
+                        let b = corners[(i + 1) % 4];
+                        (b - a).perp_dot(point - a)
+                    })
+                    .collect::<Vec<_>>();
+                sides.iter().all(|v| *v >= 0.) || sides.iter().all(|v| *v <= 0.)
+            }
+        }
+    }
+}
+
+impl From<&LevelSections> for TriggerShape {
+    fn from(section: &LevelSections) -> Self {
+        TriggerShape::Quad {
+            bottom_left: section.bottom_left,
+            top_left: section.top_left,
+            top_right: section.top_right,
+            bottom_right: section.bottom_right,
+        }
+    }
+}
+
+#[derive(Clone, Reflect, InspectorOptions, Deserialize, Debug)]
+#[serde(default)]
+pub struct TriggerDefinition {
+    pub anchor: TriggerAnchor,
+    pub shapes: Vec<TriggerShape>,
+    pub when: TriggerWhen,
+    pub interval: f32,
+    pub once: bool,
+    pub actions: Vec<LevelAction>,
+}
+
+impl Default for TriggerDefinition {
+    fn default() -> Self {
+        Self {
+            anchor: TriggerAnchor::PlayerStart,
+            shapes: vec![],
+            when: TriggerWhen::Enter,
+            interval: 1.,
+            once: true,
+            actions: vec![],
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct TriggerVolume {
+    definition: TriggerDefinition,
+    inside: Vec<(Entity, f32)>,
+    fired: bool,
+}
+
+impl TriggerVolume {
+    fn contains(&self, point: Vec2) -> bool {
+        self.definition
+            .shapes
+            .iter()
+            .any(|shape| shape.contains(point))
+    }
+}
+
+pub fn spawn_triggers(commands: &mut Commands, level: &CurrentLevel, shape: &LevelShape) {
+    for definition in level.triggers.iter() {
+        let anchor = match definition.anchor {
+            TriggerAnchor::PlayerStart => shape.player_start_point,
+            TriggerAnchor::Crossroads => shape.crossroads,
+        };
+        let definition = TriggerDefinition {
+            shapes: definition.shapes.iter().map(|v| v.offset(anchor)).collect(),
+            ..definition.clone()
+        };
+        spawn_trigger(commands, definition);
+    }
+
+    for (index, segment) in level.segments.iter().enumerate() {
+        if segment.on_enter.is_empty() {
+            continue;
+        }
+        let shapes = shape
+            .section
+            .iter()
+            .filter(|section| section.id == index)
+            .map(TriggerShape::from)
+            .collect();
+        spawn_trigger(
+            commands,
+            TriggerDefinition {
+                shapes,
+                actions: segment.on_enter.clone(),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn spawn_trigger(commands: &mut Commands, definition: TriggerDefinition) {
+    commands.spawn((
+        Name::new("Trigger Volume"),
+        TriggerVolume {
+            definition,
+            inside: vec![],
+            fired: false,
+        },
+        InGame,
+    ));
+}
+
+fn check_triggers(
+    mut triggers: Query<&mut TriggerVolume>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut actions: EventWriter<RunLevelAction>,
+    time: Res<GameClock>,
+) {
+    let delta = time.delta_seconds();
+    for mut trigger in &mut triggers {
+        if trigger.fired && trigger.definition.once {
+            continue;
+        }
+        let mut fire_for = vec![];
+
+        for (player, transform) in &players {
+            let inside = trigger.contains(transform.translation().xy());
+            let index = trigger
+                .inside
+                .iter()
+                .position(|(entity, _)| *entity == player);
+            let when = trigger.definition.when;
+            let interval = trigger.definition.interval;
+
+            match (inside, index) {
+                (true, None) => {
+                    trigger.inside.push((player, 0.));
+                    if when == TriggerWhen::Enter {
+                        fire_for.push(player);
+                    }
+                }
+                (false, Some(index)) => {
+                    trigger.inside.remove(index);
+                    if when == TriggerWhen::Leave {
+                        fire_for.push(player);
+                    }
+                }
+                (true, Some(index)) if when == TriggerWhen::Stay => {
+                    let stayed = &mut trigger.inside[index].1;
+                    *stayed += delta;
+                    if *stayed >= interval {
+                        *stayed = 0.;
+                        fire_for.push(player);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        trigger
+            .inside
+            .retain(|(entity, _)| players.contains(*entity));
+
+        for player in fire_for {
+            if trigger.fired && trigger.definition.once {
+                break;
+            }
+            trigger.fired = true;
+            for action in trigger.definition.actions.iter() {
+                actions.send(RunLevelAction {
+                    action: action.clone(),
+                    player: Some(player),
+                });
+            }
+        }
+    }
+}
+
+fn draw_triggers(
+    triggers: Query<&TriggerVolume>,
+    mut painter: ShapePainter,
+    gizmos: Res<DrawDebugGizmos>,
+) {
+    if !matches!(gizmos.as_ref(), DrawDebugGizmos::Collision) {
+        return;
+    }
+    painter.hollow = true;
+    painter.thickness = 2.;
+
+    for trigger in &triggers {
+        painter.color = if trigger.fired {
+            crate::ui::colors::BAD_COLOR.with_a(0.4)
+        } else {
+            crate::ui::colors::PRIMARY_COLOR.with_a(0.6)
+        };
+        for shape in trigger.definition.shapes.iter() {
+            match shape {
+                TriggerShape::Circle { center, radius } => {
+                    painter.set_translation(center.extend(3.));
+                    painter.circle(*radius);
+                }
+                TriggerShape::Quad {
+                    bottom_left,
+                    top_left,
+                    top_right,
+                    bottom_right,
+                } => {
+                    painter.set_translation(Vec3::ZERO);
+                    let corners = [*bottom_left, *top_left, *top_right, *bottom_right];
+                    for i in 0..4 {
+                        painter.line(corners[i].extend(3.), corners[(i + 1) % 4].extend(3.));
+                    }
+                }
+            }
+        }
+    }
+}
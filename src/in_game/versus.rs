@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use dexterous_developer::{ReloadableApp, ReloadableAppContents};
+
+use crate::app_state::AppState;
+
+use super::{
+    aim_assist::AimAssist,
+    co_op::{GameMode, PlayerIndex},
+    escort::DangerTarget,
+    game_state::GameState,
+    movement::CanMove,
+    player::Player,
+    ritual::{Ritual, TimeSoFar},
+    schedule::{InGamePostUpdate, InGameUpdate},
+    shadow::CheckForShadow,
+    souls::SunSensitivity,
+    teleport::CanTeleport,
+};
+
+pub fn versus_plugin(app: &mut ReloadableAppContents) {
+    app.add_systems(OnEnter(AppState::InGame), reset_race)
+        .add_systems(InGameUpdate, track_arrivals)
+        .add_systems(InGamePostUpdate, finish_race);
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RaceResults {
+    pub arrivals: Vec<(usize, f32)>,
+    pub eliminated: Vec<usize>,
+}
+
+impl RaceResults {
+    pub fn ranking(&self) -> Vec<(usize, Option<f32>)> {
+        self.arrivals
+            .iter()
+            .map(|(player, time)| (*player, Some(*time)))
+            .chain(self.eliminated.iter().rev().map(|player| (*player, None)))
+            .collect()
+    }
+}
+
+#[derive(Component)]
+pub struct Arrived;
+
+fn reset_race(mut commands: Commands) {
+    commands.insert_resource(RaceResults::default());
+}
+
+fn track_arrivals(
+    mode: Res<GameMode>,
+    mut results: ResMut<RaceResults>,
+    players: Query<(Entity, &GlobalTransform, &PlayerIndex), With<Player>>,
+    rituals: Query<(&Ritual, &TimeSoFar)>,
+    mut commands: Commands,
+) {
+    if !mode.is_versus() {
+        return;
+    }
+    let Ok((ritual, time)) = rituals.get_single() else {
+        return;
+    };
+    let position = ritual.position.extend(0.);
+    for (player, transform, index) in &players {
+        if transform.translation().distance(position) > ritual.radius {
+            continue;
+        }
+        results.arrivals.push((index.0, time.0));
+        commands
+            .entity(player)
+            .remove::<(
+                Player,
+                DangerTarget,
+                CheckForShadow,
+                SunSensitivity,
+                CanMove,
+                CanTeleport,
+                AimAssist,
+            )>()
+            .insert(Arrived);
+    }
+}
+
+fn finish_race(
+    mode: Res<GameMode>,
+    results: Res<RaceResults>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    if !mode.is_versus() || results.arrivals.is_empty() || !players.is_empty() {
+        return;
+    }
+    commands.insert_resource(NextState(Some(GameState::Complete)));
+}
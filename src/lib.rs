@@ -4,6 +4,9 @@ mod app_state;
 mod assets;
 mod in_game;
 mod menus;
+mod persistence;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod song_analysis;
 mod toon_material;
 mod ui;
 
@@ -30,7 +33,10 @@ use bevy_tweening::TweeningPlugin;
 use bevy_vector_shapes::Shape2dPlugin;
 use credits::CreditsPlugin;
 use dexterous_developer::{hot_bevy_main, InitialPlugins};
-use in_game::{InGamePlugin, Levels, TrackingCamera};
+use in_game::{
+    Controls, CurrentLevel, InGamePlugin, Levels, Progression, TrackingCamera, CONTROLS_FILE,
+    PROGRESSION_FILE,
+};
 use loading_state::LoadingScreenPlugin;
 use menu::MainMenuPlugin;
 use menus::{credits, loading_state, menu};
@@ -39,7 +45,10 @@ use ui::{colors::DEFAULT_AMBIENT, UiPlugin};
 
 use crate::{
     app_state::DrawDebugGizmos,
-    menus::{levels::LevelsPlugin, next_level::NextLevelPlugin},
+    menus::{
+        controls::ControlsPlugin, levels::LevelsPlugin, next_level::NextLevelPlugin,
+        upgrades::UpgradesPlugin,
+    },
 };
 
 #[hot_bevy_main]
@@ -75,10 +84,14 @@ fn bevy_main(initial: impl InitialPlugins) {
         .insert_resource(DEFAULT_AMBIENT)
         .init_resource::<DrawDebugGizmos>()
         .init_resource::<Levels>()
+        .insert_resource(persistence::load::<Controls>(CONTROLS_FILE).with_missing_defaults())
+        .insert_resource(persistence::load::<Progression>(PROGRESSION_FILE))
         .add_plugins((
             LoadingScreenPlugin,
             MainMenuPlugin,
             CreditsPlugin,
+            ControlsPlugin,
+            UpgradesPlugin,
             InGamePlugin,
             LevelsPlugin,
             NextLevelPlugin,
@@ -127,13 +140,33 @@ fn insert_level_list(
     assets: Res<MainGameAssets>,
     levels: Res<Assets<Levels>>,
 ) {
-    let levels = levels.get(&assets.levels).cloned().unwrap_or_default();
+    let mut levels = levels.get(&assets.levels).cloned().unwrap_or_default();
+    levels.0.extend(custom_levels());
     let current = levels.0.get(0).cloned().unwrap_or_default();
     commands.insert_resource(levels);
     commands.insert_resource(current);
     commands.insert_resource(CurrentLevelID::default())
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn custom_levels() -> Vec<CurrentLevel> {
+    let Ok(contents) = std::fs::read_to_string(song_analysis::CUSTOM_LEVELS_FILE) else {
+        return vec![];
+    };
+    match serde_yaml::from_str::<Levels>(&contents) {
+        Ok(levels) => levels.0,
+        Err(e) => {
+            error!("Couldn't parse custom levels: {e}");
+            vec![]
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn custom_levels() -> Vec<CurrentLevel> {
+    vec![]
+}
+
 #[derive(Clone, Copy, Resource, Reflect, InspectorOptions, Default)]
 #[reflect(Resource, InspectorOptions)]
 struct CurrentLevelID(usize);
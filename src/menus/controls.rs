@@ -0,0 +1,304 @@
+use bevy::prelude::*;
+use bevy_ui_dsl::*;
+use dexterous_developer::{
+    dexterous_developer_setup, ReloadableApp, ReloadableAppContents, ReloadableElementsSetup,
+};
+use leafwing_input_manager::prelude::{DualAxis, InputKind};
+
+use crate::{
+    app_state::AppState,
+    in_game::{input_label, Controls, TrackingCamera, CONTROLS_FILE},
+    persistence,
+    ui::{
+        buttons::{focus_text_button, focused_button_activated},
+        classes::*,
+        colors::SCREEN_BACKGROUND_COLOR,
+        intermediary_node_bundles::*,
+    },
+};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.setup_reloadable_elements::<reloadable>();
+    }
+}
+
+#[dexterous_developer_setup(controls)]
+fn reloadable(app: &mut ReloadableAppContents) {
+    app.reset_setup_in_state::<Screen, _, _>(AppState::Controls, setup)
+        .add_systems(
+            Update,
+            (
+                focused_button_activated.pipe(process_input),
+                capture_binding,
+                update_binding_labels,
+            )
+                .run_if(in_state(AppState::Controls)),
+        );
+}
+
+#[derive(Component)]
+struct Screen;
+
+#[derive(Component, Copy, Clone)]
+enum ControlsButton {
+    Binding(usize),
+    Reset,
+    Menu,
+}
+
+#[derive(Component)]
+struct ControlsStatus;
+
+#[derive(Resource)]
+struct AwaitingBinding(usize);
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    controls: Res<Controls>,
+    mut camera: Query<&mut Transform, With<TrackingCamera>>,
+) {
+    commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
+
+    let mut buttons = vec![];
+    let mut status = None;
+
+    let r = root(c_root, &asset_server, &mut commands, |p| {
+        node(primary_box, p, |p| {
+            node((span.nb(), primary_box_main.nb()), p, |p| {
+                text("Controls", (), main_text, p);
+            });
+
+            node(controls_grid, p, |p| {
+                for (i, binding) in controls.bindings.iter().enumerate() {
+                    text(binding.label(), (), standard_text, p);
+                    let button = focus_text_button(
+                        input_label(&binding.input),
+                        (c_button.nb(), binding_button.nb()),
+                        apply_binding_button_state,
+                        button_text,
+                        p,
+                    );
+                    buttons.push((button, ControlsButton::Binding(i)));
+                }
+            });
+
+            status = Some(text("", primary_box_item.nb(), standard_text, p));
+
+            let button = focus_text_button(
+                "Reset to Defaults",
+                (c_button.nb(), primary_box_item.nb()),
+                apply_button_state,
+                button_text,
+                p,
+            );
+            buttons.push((button, ControlsButton::Reset));
+
+            let button = focus_text_button(
+                "Main Menu",
+                (c_button.nb(), primary_box_item.nb()),
+                apply_button_state,
+                button_text,
+                p,
+            );
+            buttons.push((button, ControlsButton::Menu));
+        });
+    });
+    commands.entity(r).insert(Screen);
+    for (button, controls_button) in buttons.iter() {
+        commands.entity(*button).insert(*controls_button);
+    }
+    if let Some(status) = status {
+        commands.entity(status).insert(ControlsStatus);
+    }
+    for mut camera in &mut camera {
+        camera.translation = Vec3::new(0., 0., 5.);
+        camera.look_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn set_status(status: &mut Query<&mut Text, With<ControlsStatus>>, value: String) {
+    for mut text in status.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = value.clone();
+        }
+    }
+}
+
+fn process_input(
+    In(focused): In<Option<Entity>>,
+    buttons: Query<&ControlsButton>,
+    awaiting: Option<Res<AwaitingBinding>>,
+    mut controls: ResMut<Controls>,
+    mut status: Query<&mut Text, With<ControlsStatus>>,
+    mut commands: Commands,
+) {
+    if awaiting.is_some() {
+        return;
+    }
+    let Some(entity) = focused else {
+        return;
+    };
+    let Ok(button) = buttons.get(entity) else {
+        return;
+    };
+    match button {
+        ControlsButton::Binding(i) => {
+            let Some(binding) = controls.bindings.get(*i) else {
+                return;
+            };
+            let prompt = if binding.is_axis() {
+                "Move a stick"
+            } else {
+                "Press an input"
+            };
+            set_status(
+                &mut status,
+                format!("{prompt} for {} - Escape to cancel", binding.label()),
+            );
+            commands.insert_resource(AwaitingBinding(*i));
+        }
+        ControlsButton::Reset => {
+            *controls = Controls::default();
+            persistence::save(CONTROLS_FILE, controls.as_ref());
+            set_status(&mut status, "Controls reset to defaults".to_string());
+        }
+        ControlsButton::Menu => commands.insert_resource(NextState(Some(AppState::MainMenu))),
+    };
+}
+
+fn capture_binding(
+    awaiting: Option<Res<AwaitingBinding>>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut controls: ResMut<Controls>,
+    mut status: Query<&mut Text, With<ControlsStatus>>,
+    mut commands: Commands,
+) {
+    let Some(awaiting) = awaiting else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<AwaitingBinding>();
+        set_status(&mut status, String::new());
+        return;
+    }
+
+    let Some(expects_axis) = controls.bindings.get(awaiting.0).map(|v| v.is_axis()) else {
+        commands.remove_resource::<AwaitingBinding>();
+        return;
+    };
+
+    let stick = |x: GamepadAxisType, y: GamepadAxisType| {
+        gamepads.iter().any(|gamepad| {
+            [x, y].into_iter().any(|axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .map(|value| value.abs() > STICK_THRESHOLD)
+                    .unwrap_or_default()
+            })
+        })
+    };
+
+    let input = if expects_axis {
+        if stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY) {
+            Some(InputKind::DualAxis(DualAxis::left_stick()))
+        } else if stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY) {
+            Some(InputKind::DualAxis(DualAxis::right_stick()))
+        } else {
+            None
+        }
+    } else {
+        keys.get_just_pressed()
+            .next()
+            .map(|key| InputKind::Keyboard(*key))
+            .or_else(|| {
+                mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| InputKind::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepad
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| InputKind::GamepadButton(button.button_type))
+            })
+    };
+
+    let Some(input) = input else {
+        return;
+    };
+    commands.remove_resource::<AwaitingBinding>();
+
+    if let Some(conflict) = controls.conflict(awaiting.0, &input) {
+        set_status(
+            &mut status,
+            format!(
+                "{} is already bound to {}",
+                input_label(&input),
+                conflict.label()
+            ),
+        );
+        return;
+    }
+
+    let Some(binding) = controls.bindings.get_mut(awaiting.0) else {
+        return;
+    };
+    if !binding.accepts(&input) {
+        set_status(
+            &mut status,
+            format!(
+                "{} can't be bound to {}",
+                binding.label(),
+                input_label(&input)
+            ),
+        );
+        return;
+    }
+    binding.input = input;
+    let message = format!("Bound {} to {}", binding.label(), input_label(&input));
+    persistence::save(CONTROLS_FILE, controls.as_ref());
+    set_status(&mut status, message);
+}
+
+fn update_binding_labels(
+    controls: Res<Controls>,
+    awaiting: Option<Res<AwaitingBinding>>,
+    buttons: Query<(&ControlsButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (button, children) in &buttons {
+        let ControlsButton::Binding(i) = button else {
+            continue;
+        };
+        let label = if awaiting.as_ref().map(|v| v.0 == *i).unwrap_or(false) {
+            "...".to_string()
+        } else {
+            controls
+                .bindings
+                .get(*i)
+                .map(|v| input_label(&v.input))
+                .unwrap_or_default()
+        };
+        for child in children.iter() {
+            let Ok(mut text) = texts.get_mut(*child) else {
+                continue;
+            };
+            if let Some(section) = text.sections.first_mut() {
+                if section.value != label {
+                    section.value = label.clone();
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use bevy_turborand::{DelegatedRng, GlobalRng};
 use bevy_ui_dsl::*;
 use dexterous_developer::{
     dexterous_developer_setup, ReloadableApp, ReloadableAppContents, ReloadableElementsSetup,
@@ -8,7 +9,7 @@ use dexterous_developer::{
 use crate::{
     app_state::AppState,
     assets::WithMesh,
-    in_game::{Levels, TrackingCamera},
+    in_game::{GameMode, Levels, TrackingCamera},
     ui::{
         buttons::{focus_text_button, focused_button_activated},
         classes::*,
@@ -32,7 +33,11 @@ fn reloadable(app: &mut ReloadableAppContents) {
     app.reset_setup_in_state::<Screen, _, _>(AppState::Levels, setup)
         .add_systems(
             Update,
-            (focused_button_activated.pipe(process_input)).run_if(in_state(AppState::Levels)),
+            (
+                focused_button_activated.pipe(process_input),
+                update_mode_labels,
+            )
+                .run_if(in_state(AppState::Levels)),
         );
 }
 
@@ -42,6 +47,11 @@ struct Screen;
 #[derive(Component, Copy, Clone)]
 enum LevelButton {
     Level(usize),
+    Players,
+    Style,
+    Checkpoints,
+    RitualRule,
+    SavePoints,
     Menu,
 }
 
@@ -49,6 +59,7 @@ fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     levels: Res<Levels>,
+    mode: Res<GameMode>,
     mut camera: Query<&mut Transform, With<TrackingCamera>>,
 ) {
     commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
@@ -70,6 +81,32 @@ fn setup(
                 buttons.push((button, LevelButton::Level(i)));
             }
 
+            for (label, button_type) in [
+                (mode.players_label(), LevelButton::Players),
+                (mode.style.label().to_string(), LevelButton::Style),
+                (
+                    mode.checkpoints.label().to_string(),
+                    LevelButton::Checkpoints,
+                ),
+                (
+                    mode.ritual_rule.label().to_string(),
+                    LevelButton::RitualRule,
+                ),
+                (
+                    mode.save_points_label().to_string(),
+                    LevelButton::SavePoints,
+                ),
+            ] {
+                let button = focus_text_button(
+                    label,
+                    (c_button.nb(), primary_box_item.nb()),
+                    apply_button_state,
+                    button_text,
+                    p,
+                );
+                buttons.push((button, button_type));
+            }
+
             let button = focus_text_button(
                 "Main Menu",
                 (c_button.nb(), primary_box_item.nb()),
@@ -117,6 +154,8 @@ fn process_input(
     buttons: Query<&LevelButton>,
     mut commands: Commands,
     levels: Res<Levels>,
+    mut mode: ResMut<GameMode>,
+    mut rng: ResMut<GlobalRng>,
 ) {
     let Some(entity) = focused else {
         return;
@@ -132,6 +171,41 @@ fn process_input(
                 commands.insert_resource(NextState(Some(AppState::ToNextLevel)))
             }
         }
+        LevelButton::Players => mode.players = mode.next_player_count(),
+        LevelButton::Style => {
+            mode.style = mode.style.next();
+            mode.race_seed = rng.get_mut().u64(..);
+        }
+        LevelButton::Checkpoints => mode.checkpoints = mode.checkpoints.next(),
+        LevelButton::RitualRule => mode.ritual_rule = mode.ritual_rule.next(),
+        LevelButton::SavePoints => mode.save_points = !mode.save_points,
         LevelButton::Menu => commands.insert_resource(NextState(Some(AppState::MainMenu))),
     };
 }
+
+fn update_mode_labels(
+    mode: Res<GameMode>,
+    buttons: Query<(&LevelButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !mode.is_changed() {
+        return;
+    }
+    for (button, children) in &buttons {
+        let label = match button {
+            LevelButton::Players => mode.players_label(),
+            LevelButton::Style => mode.style.label().to_string(),
+            LevelButton::Checkpoints => mode.checkpoints.label().to_string(),
+            LevelButton::RitualRule => mode.ritual_rule.label().to_string(),
+            LevelButton::SavePoints => mode.save_points_label().to_string(),
+            _ => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if let Some(section) = text.sections.first_mut() {
+                    section.value = label.clone();
+                }
+            }
+        }
+    }
+}
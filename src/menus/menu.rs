@@ -43,6 +43,8 @@ struct Screen;
 #[derive(Component)]
 enum Buttons {
     Start,
+    Upgrades,
+    Controls,
     Credits,
 }
 
@@ -55,6 +57,8 @@ fn setup(
     commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
 
     let mut start_button = None;
+    let mut upgrades_button = None;
+    let mut controls_button = None;
     let mut credits_button = None;
 
     let r = root(c_root, &asset_server, &mut commands, |p| {
@@ -68,6 +72,22 @@ fn setup(
                 p,
             )
             .set(&mut start_button);
+            focus_text_button(
+                "Upgrades",
+                (c_button.nb(), primary_box_item.nb()),
+                apply_button_state,
+                button_text,
+                p,
+            )
+            .set(&mut upgrades_button);
+            focus_text_button(
+                "Controls",
+                (c_button.nb(), primary_box_item.nb()),
+                apply_button_state,
+                button_text,
+                p,
+            )
+            .set(&mut controls_button);
             focus_text_button(
                 "Credits",
                 (c_button.nb(), primary_box_item.nb()),
@@ -82,6 +102,12 @@ fn setup(
     commands
         .entity(start_button.unwrap())
         .insert(Buttons::Start);
+    commands
+        .entity(upgrades_button.unwrap())
+        .insert(Buttons::Upgrades);
+    commands
+        .entity(controls_button.unwrap())
+        .insert(Buttons::Controls);
     commands
         .entity(credits_button.unwrap())
         .insert(Buttons::Credits);
@@ -127,6 +153,8 @@ fn process_input(
     };
     match btn {
         Buttons::Start => commands.insert_resource(NextState(Some(AppState::Levels))),
+        Buttons::Upgrades => commands.insert_resource(NextState(Some(AppState::Upgrades))),
+        Buttons::Controls => commands.insert_resource(NextState(Some(AppState::Controls))),
         Buttons::Credits => commands.insert_resource(NextState(Some(AppState::Credits))),
     };
 }
@@ -1,6 +1,8 @@
+pub mod controls;
 pub mod credits;
 mod game_title;
 pub mod levels;
 pub mod loading_state;
 pub mod menu;
 pub mod next_level;
+pub mod upgrades;
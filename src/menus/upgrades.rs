@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy_ui_dsl::*;
+use dexterous_developer::{
+    dexterous_developer_setup, ReloadableApp, ReloadableAppContents, ReloadableElementsSetup,
+};
+
+use crate::{
+    app_state::AppState,
+    in_game::{Progression, TrackingCamera, Upgrade, PROGRESSION_FILE},
+    persistence,
+    ui::{
+        buttons::{focus_text_button, focused_button_activated},
+        classes::*,
+        colors::SCREEN_BACKGROUND_COLOR,
+        intermediary_node_bundles::*,
+    },
+};
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.setup_reloadable_elements::<reloadable>();
+    }
+}
+
+#[dexterous_developer_setup(upgrades)]
+fn reloadable(app: &mut ReloadableAppContents) {
+    app.reset_setup_in_state::<Screen, _, _>(AppState::Upgrades, setup)
+        .add_systems(
+            Update,
+            (
+                focused_button_activated.pipe(process_input),
+                update_upgrade_labels,
+            )
+                .run_if(in_state(AppState::Upgrades)),
+        );
+}
+
+#[derive(Component)]
+struct Screen;
+
+#[derive(Component, Copy, Clone)]
+enum UpgradesButton {
+    Buy(Upgrade),
+    Menu,
+}
+
+#[derive(Component)]
+struct ScoreText;
+
+fn upgrade_label(progression: &Progression, upgrade: Upgrade) -> String {
+    let level = progression.level(upgrade);
+    let max = upgrade.max_level();
+    match progression.next_cost(upgrade) {
+        Some(cost) => format!("{} {level}/{max} - {cost}", upgrade.label()),
+        None => format!("{} {level}/{max} - Maxed", upgrade.label()),
+    }
+}
+
+fn score_label(progression: &Progression) -> String {
+    format!("Score: {}", progression.score)
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    progression: Res<Progression>,
+    mut camera: Query<&mut Transform, With<TrackingCamera>>,
+) {
+    commands.insert_resource(ClearColor(SCREEN_BACKGROUND_COLOR));
+
+    let mut buttons = vec![];
+    let mut score = None;
+
+    let r = root(c_root, &asset_server, &mut commands, |p| {
+        node(primary_box, p, |p| {
+            node((span.nb(), primary_box_main.nb()), p, |p| {
+                text("Upgrades", (), main_text, p);
+            });
+
+            score = Some(text(
+                score_label(&progression),
+                primary_box_item.nb(),
+                standard_text,
+                p,
+            ));
+
+            for upgrade in Upgrade::ALL {
+                let button = focus_text_button(
+                    upgrade_label(&progression, upgrade),
+                    (c_button.nb(), primary_box_item.nb()),
+                    apply_button_state,
+                    button_text,
+                    p,
+                );
+                buttons.push((button, UpgradesButton::Buy(upgrade)));
+            }
+
+            let button = focus_text_button(
+                "Main Menu",
+                (c_button.nb(), primary_box_item.nb()),
+                apply_button_state,
+                button_text,
+                p,
+            );
+            buttons.push((button, UpgradesButton::Menu));
+        });
+    });
+    commands.entity(r).insert(Screen);
+    for (button, upgrades_button) in buttons.iter() {
+        commands.entity(*button).insert(*upgrades_button);
+    }
+    if let Some(score) = score {
+        commands.entity(score).insert(ScoreText);
+    }
+    for mut camera in &mut camera {
+        camera.translation = Vec3::new(0., 0., 5.);
+        camera.look_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn process_input(
+    In(focused): In<Option<Entity>>,
+    buttons: Query<&UpgradesButton>,
+    mut progression: ResMut<Progression>,
+    mut commands: Commands,
+) {
+    let Some(entity) = focused else {
+        return;
+    };
+    let Ok(button) = buttons.get(entity) else {
+        return;
+    };
+    match button {
+        UpgradesButton::Buy(upgrade) => {
+            if progression.buy(*upgrade) {
+                persistence::save(PROGRESSION_FILE, progression.as_ref());
+            }
+        }
+        UpgradesButton::Menu => commands.insert_resource(NextState(Some(AppState::MainMenu))),
+    };
+}
+
+fn update_upgrade_labels(
+    progression: Res<Progression>,
+    buttons: Query<(&UpgradesButton, &Children)>,
+    mut score: Query<&mut Text, With<ScoreText>>,
+    mut texts: Query<&mut Text, Without<ScoreText>>,
+) {
+    if !progression.is_changed() {
+        return;
+    }
+    for mut text in &mut score {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = score_label(&progression);
+        }
+    }
+    for (button, children) in &buttons {
+        let UpgradesButton::Buy(upgrade) = button else {
+            continue;
+        };
+        let label = upgrade_label(&progression, *upgrade);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if let Some(section) = text.sections.first_mut() {
+                    section.value = label.clone();
+                }
+            }
+        }
+    }
+}
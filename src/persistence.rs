@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+const SETTINGS_DIRECTORY: &str = "settings";
+
+fn settings_path(name: &str) -> PathBuf {
+    PathBuf::from(SETTINGS_DIRECTORY).join(format!("{name}.yaml"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let path = settings_path(name);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return T::default();
+    };
+    match serde_yaml::from_str(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            error!("Couldn't parse {path:?}: {e}");
+            T::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let path = settings_path(name);
+    let contents = match serde_yaml::to_string(value) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("Couldn't serialize {name}: {e}");
+            return;
+        }
+    };
+    if let Err(e) =
+        std::fs::create_dir_all(SETTINGS_DIRECTORY).and_then(|_| std::fs::write(&path, contents))
+    {
+        error!("Couldn't write {path:?}: {e}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned + Default>(_name: &str) -> T {
+    T::default()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save<T: Serialize>(name: &str, _value: &T) {
+    info!(
+        "Can't persist {name:?} to {:?} on the web",
+        settings_path(name)
+    );
+}
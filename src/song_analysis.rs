@@ -0,0 +1,175 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use rodio::{Decoder, Source};
+use serde::Serialize;
+
+use crate::{
+    assets::Locale,
+    in_game::{DangerType, Segment, TempoSettings},
+};
+
+pub const ASSETS_DIRECTORY: &str = "assets";
+pub const CUSTOM_MUSIC_DIRECTORY: &str = "assets/custom_music";
+pub const CUSTOM_LEVELS_FILE: &str = "assets/custom_music/levels.lvl.yaml";
+
+const ENVELOPE_WINDOW: f32 = 0.01;
+const MIN_BPM: f32 = 70.;
+const MAX_BPM: f32 = 180.;
+const SECONDS_PER_SEGMENT: f32 = 12.;
+const MIN_SEGMENTS: usize = 3;
+const MAX_SEGMENTS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct SongAnalysis {
+    pub duration: f32,
+    pub bpm: f32,
+    pub offset: f32,
+    pub envelope: Vec<f32>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct GeneratedLevel {
+    pub name: String,
+    pub song: String,
+    pub song_length: f32,
+    pub curviness: f32,
+    pub locale: Locale,
+    pub tempo: TempoSettings,
+    pub segments: Vec<Segment>,
+}
+
+pub fn decode(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let file = File::open(path).map_err(|e| format!("Couldn't open {path:?}: {e}"))?;
+    let decoder =
+        Decoder::new(BufReader::new(file)).map_err(|e| format!("Couldn't decode {path:?}: {e}"))?;
+    let channels = decoder.channels().max(1) as usize;
+    let sample_rate = decoder.sample_rate();
+    let samples = decoder.convert_samples::<f32>().collect::<Vec<_>>();
+    let mono = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok((mono, sample_rate))
+}
+
+pub fn analyze(samples: &[f32], sample_rate: u32) -> SongAnalysis {
+    let duration = samples.len() as f32 / sample_rate.max(1) as f32;
+    let window = ((sample_rate as f32 * ENVELOPE_WINDOW) as usize).max(1);
+
+    let mut envelope = samples
+        .chunks(window)
+        .map(|chunk| (chunk.iter().map(|v| v * v).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect::<Vec<_>>();
+    let peak = envelope.iter().copied().fold(0., f32::max);
+    if peak > 0. {
+        envelope.iter_mut().for_each(|v| *v /= peak);
+    }
+
+    let onsets = envelope
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).max(0.))
+        .collect::<Vec<_>>();
+
+    let min_lag = (60. / (MAX_BPM * ENVELOPE_WINDOW)) as usize;
+    let max_lag = (60. / (MIN_BPM * ENVELOPE_WINDOW)) as usize;
+    let lag = (min_lag..=max_lag)
+        .map(|lag| {
+            let correlation = onsets
+                .iter()
+                .zip(onsets.iter().skip(lag))
+                .map(|(a, b)| a * b)
+                .sum::<f32>();
+            (lag, correlation)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(lag, _)| lag)
+        .unwrap_or(min_lag)
+        .max(1);
+
+    let phase = (0..lag)
+        .map(|phase| {
+            let strength = onsets.iter().skip(phase).step_by(lag).sum::<f32>();
+            (phase, strength)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(phase, _)| phase)
+        .unwrap_or_default();
+
+    SongAnalysis {
+        duration,
+        bpm: 60. / (lag as f32 * ENVELOPE_WINDOW),
+        offset: phase as f32 * ENVELOPE_WINDOW,
+        envelope,
+    }
+}
+
+fn segment_intensities(analysis: &SongAnalysis) -> Vec<f32> {
+    let count =
+        ((analysis.duration / SECONDS_PER_SEGMENT) as usize).clamp(MIN_SEGMENTS, MAX_SEGMENTS);
+    let chunk = (analysis.envelope.len() / count).max(1);
+    let means = analysis
+        .envelope
+        .chunks(chunk)
+        .take(count)
+        .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+        .collect::<Vec<_>>();
+    let loudest = means.iter().copied().fold(0., f32::max);
+    means
+        .into_iter()
+        .map(|mean| if loudest > 0. { mean / loudest } else { 0. })
+        .collect()
+}
+
+fn danger_mix(intensity: f32) -> Vec<(DangerType, f32)> {
+    [
+        (DangerType::LumberingDevil, 0., 0.3),
+        (DangerType::HolyHulk, 0.3, 0.5),
+        (DangerType::StealthySeraphim, 0.5, 0.5),
+        (DangerType::AngelicArcher, 0.7, 0.3),
+        (DangerType::DivineDetonator, 0.85, 0.2),
+    ]
+    .into_iter()
+    .filter(|(_, threshold, _)| intensity > *threshold)
+    .map(|(danger, _, weight)| (danger, weight * intensity))
+    .collect()
+}
+
+pub fn generate_level(name: &str, song: &str, analysis: &SongAnalysis) -> GeneratedLevel {
+    let intensities = segment_intensities(analysis);
+    let average = intensities.iter().sum::<f32>() / intensities.len().max(1) as f32;
+
+    let segments = intensities
+        .iter()
+        .enumerate()
+        .map(|(index, intensity)| Segment {
+            tree_density: 0.9 - 0.5 * intensity,
+            checkpoint_density: 0.1 + 0.2 * intensity,
+            danger_densities: if index == 0 {
+                vec![]
+            } else {
+                danger_mix(*intensity)
+            },
+            ..Default::default()
+        })
+        .collect();
+
+    GeneratedLevel {
+        name: name.to_string(),
+        song: song.to_string(),
+        song_length: analysis.duration.floor(),
+        curviness: 100. + 150. * average,
+        locale: if average > 0.7 {
+            Locale::Hell
+        } else if analysis.bpm < 100. {
+            Locale::Snow
+        } else {
+            Locale::Forest
+        },
+        tempo: TempoSettings {
+            bpm: analysis.bpm,
+            offset: analysis.offset,
+            ..Default::default()
+        },
+        segments,
+    }
+}
@@ -93,6 +93,38 @@ pub fn apply_button_state(state: FocusState) -> NodeBundle {
     bundle
 }
 
+pub fn controls_grid(b: &mut NodeBundle) {
+    b.style.grid_column = GridPlacement::start(2).set_span(1);
+    b.style.display = Display::Grid;
+    b.style.grid_template_columns = vec![
+        GridTrack::auto(),
+        GridTrack::auto(),
+        GridTrack::auto(),
+        GridTrack::auto(),
+    ];
+    b.style.align_items = AlignItems::Center;
+    b.style.column_gap = Val::Px(10.);
+}
+
+pub fn binding_button(b: &mut dyn IntermediaryNodeBundleHandler) {
+    b.style().padding = UiRect::all(Val::Px(5.));
+    b.style().margin = UiRect::all(Val::Px(3.));
+}
+
+pub fn apply_binding_button_state(state: FocusState) -> NodeBundle {
+    let mut bundle = NodeBundle::default();
+    c_button(&mut bundle);
+    binding_button(&mut bundle);
+    match state {
+        FocusState::Prioritized => c_button_prioritized(&mut bundle),
+        FocusState::Focused => c_button_focused(&mut bundle),
+        FocusState::Active => c_button_active(&mut bundle),
+        FocusState::Blocked => c_button_blocked(&mut bundle),
+        FocusState::Inert => {}
+    };
+    bundle
+}
+
 pub fn button_text(assets: &AssetServer, t: &mut TextStyle) {
     t.font_size = 10.;
     t.color = colors::BORDER_COLOR;
@@ -151,6 +183,72 @@ pub fn soul_bar(b: &mut NodeBundle) {
     b.focus_policy = FocusPolicy::Pass;
 }
 
+pub fn energy_bar_container(b: &mut NodeBundle) {
+    b.style.width = Val::Vw(10.);
+    b.style.height = Val::Px(8.);
+    b.style.margin = UiRect::top(Val::Px(4.));
+    b.style.display = Display::Flex;
+    b.style.flex_direction = FlexDirection::Row;
+    b.style.justify_content = JustifyContent::FlexStart;
+    b.style.align_items = AlignItems::Stretch;
+    b.background_color.0 = colors::BORDER_COLOR;
+    b.focus_policy = FocusPolicy::Pass;
+}
+
+pub fn energy_bar(b: &mut NodeBundle) {
+    b.background_color.0 = colors::PRIMARY_COLOR_ACTIVE;
+    b.style.height = Val::Percent(100.);
+    b.style.flex_grow = 0.;
+    b.style.flex_shrink = 0.;
+    b.focus_policy = FocusPolicy::Pass;
+}
+
+pub fn boss_bar_root(b: &mut NodeBundle) {
+    b.style.width = Val::Percent(100.);
+    b.style.display = Display::Flex;
+    b.style.flex_direction = FlexDirection::Column;
+    b.style.justify_content = JustifyContent::FlexStart;
+    b.style.align_items = AlignItems::Center;
+    b.style.position_type = PositionType::Absolute;
+    b.style.left = Val::Px(0.);
+    b.style.top = Val::Px(0.);
+    b.focus_policy = FocusPolicy::Pass;
+    b.style.padding = UiRect::all(Val::Px(10.));
+}
+
+pub fn boss_bar_container(b: &mut NodeBundle) {
+    b.style.width = Val::Vw(40.);
+    b.style.height = Val::Px(15.);
+    b.style.margin = UiRect::top(Val::Px(5.));
+    b.style.display = Display::Flex;
+    b.style.flex_direction = FlexDirection::Row;
+    b.style.justify_content = JustifyContent::FlexStart;
+    b.style.align_items = AlignItems::Stretch;
+    b.background_color.0 = colors::BORDER_COLOR;
+    b.focus_policy = FocusPolicy::Pass;
+}
+
+pub fn boss_bar(b: &mut NodeBundle) {
+    b.background_color.0 = colors::BAD_COLOR;
+    b.style.height = Val::Percent(100.);
+    b.style.width = Val::Percent(100.);
+    b.style.flex_grow = 0.;
+    b.style.flex_shrink = 0.;
+    b.focus_policy = FocusPolicy::Pass;
+}
+
+pub fn objectives_root(b: &mut NodeBundle) {
+    b.style.display = Display::Flex;
+    b.style.flex_direction = FlexDirection::Column;
+    b.style.justify_content = JustifyContent::FlexEnd;
+    b.style.align_items = AlignItems::FlexStart;
+    b.style.position_type = PositionType::Absolute;
+    b.style.left = Val::Px(0.);
+    b.style.bottom = Val::Px(0.);
+    b.focus_policy = FocusPolicy::Pass;
+    b.style.padding = UiRect::all(Val::Px(10.));
+}
+
 pub fn checkpoint_marker_root(b: &mut NodeBundle) {
     b.style.width = Val::Percent(100.);
     b.style.height = Val::Percent(100.);
@@ -170,6 +268,12 @@ pub fn checkpoint_marker(b: &mut NodeBundle) {
     b.style.margin = UiRect::all(Val::Px(5.));
 }
 
+pub fn checkpoint_souls_text(b: &mut dyn IntermediaryNodeBundleHandler) {
+    b.style().position_type = PositionType::Absolute;
+    b.style().right = Val::Px(2.);
+    b.style().bottom = Val::Px(-12.);
+}
+
 pub fn checkpoint_marker_background(assets: &AssetServer, b: &mut ImageBundle) {
     b.style.position_type = PositionType::Absolute;
     b.style.top = Val::Px(0.);